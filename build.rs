@@ -28,8 +28,18 @@ fn generate_man_page(
 ) -> io::Result<ExitStatus> {
     let mut command = Command::new("asciidoctor");
 
+    #[cfg(feature = "ascon-hash")]
+    command.args(["-a", "ascon-hash"]);
+    #[cfg(feature = "belt-hash")]
+    command.args(["-a", "belt-hash"]);
+    #[cfg(feature = "blake-hash")]
+    command.args(["-a", "blake-hash"]);
     #[cfg(feature = "gost94")]
     command.args(["-a", "gost94"]);
+    #[cfg(feature = "jh")]
+    command.args(["-a", "jh"]);
+    #[cfg(feature = "kupyna")]
+    command.args(["-a", "kupyna"]);
     #[cfg(feature = "md2")]
     command.args(["-a", "md2"]);
     #[cfg(feature = "md4")]
@@ -38,6 +48,8 @@ fn generate_man_page(
     command.args(["-a", "md-5"]);
     #[cfg(feature = "sha-1")]
     command.args(["-a", "sha-1"]);
+    #[cfg(feature = "skein")]
+    command.args(["-a", "skein"]);
     #[cfg(feature = "streebog")]
     command.args(["-a", "streebog"]);
 
@@ -0,0 +1,240 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{ensure, Result};
+
+use crate::value::{Algorithm, Checksum};
+use crate::verify::VERIFICATION_RESULT_WIDTH;
+
+/// The result of auditing a file against the known checksums.
+pub struct Audit {
+    pub file: PathBuf,
+    pub status: AuditStatus,
+}
+
+/// The status of a file in the audit, as hashdeep reports.
+#[derive(Debug, PartialEq)]
+pub enum AuditStatus {
+    /// The message digests match the known file of the same name.
+    Matched,
+    /// The message digests match the known file of the other name.
+    Moved(PathBuf),
+    /// The message digests match no known file.
+    New,
+    /// The known file is not found in the inputs.
+    Missing,
+}
+
+impl Audit {
+    /// Get the hash algorithms of the inputs which are also used by the known
+    /// checksums.
+    ///
+    /// Returns an error if there is no such algorithm, since no file can match
+    /// then.
+    pub fn shared_algorithms(
+        known: &[Checksum],
+        algorithms: &[Algorithm],
+    ) -> Result<Vec<Algorithm>> {
+        let shared: Vec<_> = algorithms
+            .iter()
+            .copied()
+            .filter(|&a| known.iter().any(|c| c.algorithm == Some(a)))
+            .collect();
+        ensure!(
+            !shared.is_empty(),
+            "The known checksums have no hash algorithm in common with the inputs"
+        );
+
+        Ok(shared)
+    }
+
+    /// Audit the checksums of the inputs against the known checksums.
+    ///
+    /// As hashdeep does, only the shared hash algorithms are compared, and a
+    /// file matches a known file if all the message digests of them and the
+    /// size, if it is known, are the same. The results of the inputs are
+    /// followed by the known files which are matched by no input.
+    pub fn check(
+        known: &[Checksum],
+        shared: &[Algorithm],
+        checksums: &[Vec<Checksum>],
+        lengths: &[u64],
+    ) -> Vec<Self> {
+        let mut known_files: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        let mut index: HashMap<_, BTreeSet<_>> = HashMap::new();
+        for checksum in known {
+            // The known files of only the other algorithms are still missing.
+            let entry = known_files.entry(checksum.file.as_path()).or_default();
+            let algorithm = match checksum.algorithm {
+                Some(a) if shared.contains(&a) => a,
+                _ => continue,
+            };
+            entry.push(checksum);
+            index
+                .entry((algorithm, checksum.digest.as_slice()))
+                .or_default()
+                .insert(checksum.file.as_path());
+        }
+        let is_match = |known: &[&Checksum], checksums: &[Checksum], length: u64| {
+            known.iter().all(|k| {
+                k.size.map_or(true, |s| s == length)
+                    && checksums
+                        .iter()
+                        .any(|c| c.algorithm == k.algorithm && c.digest == k.digest)
+            })
+        };
+
+        let mut used = HashSet::new();
+        let mut results: Vec<_> = checksums
+            .iter()
+            .zip(lengths)
+            .map(|(checksums, &length)| {
+                let file = checksums[0].file.clone();
+                // The known files which have any of the message digests.
+                let candidates: BTreeSet<&Path> = checksums
+                    .iter()
+                    .filter_map(|c| Some((c.algorithm?, c.digest.as_slice())))
+                    .filter_map(|key| index.get(&key))
+                    .flatten()
+                    .copied()
+                    .filter(|k| is_match(&known_files[k], checksums, length))
+                    .collect();
+                let status = if candidates.contains(file.as_path()) {
+                    used.insert(file.as_path().to_path_buf());
+                    AuditStatus::Matched
+                } else if let Some(moved) = candidates.into_iter().next() {
+                    used.insert(moved.to_path_buf());
+                    AuditStatus::Moved(moved.to_path_buf())
+                } else {
+                    AuditStatus::New
+                };
+
+                Self { file, status }
+            })
+            .collect();
+        results.extend(
+            known_files
+                .into_keys()
+                .filter(|file| !used.contains(*file))
+                .map(|file| Self {
+                    file: file.to_path_buf(),
+                    status: AuditStatus::Missing,
+                }),
+        );
+
+        results
+    }
+
+    /// Output the audit result.
+    pub fn output(&self) -> String {
+        let status = match self.status {
+            AuditStatus::Matched => "MATCHED".to_string(),
+            AuditStatus::Moved(ref file) => format!("MOVED (from {})", file.display()),
+            AuditStatus::New => "NEW".to_string(),
+            AuditStatus::Missing => "MISSING".to_string(),
+        };
+
+        format!(
+            "{:01$} {2}",
+            self.file.display(),
+            VERIFICATION_RESULT_WIDTH - 30,
+            status
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::value::HashAlgorithm;
+
+    fn digest(hash: HashAlgorithm, file: &str, data: &[u8]) -> Checksum {
+        Checksum::digest(hash, &(file, data))
+    }
+
+    fn statuses(results: &[Audit]) -> Vec<(&Path, &AuditStatus)> {
+        results
+            .iter()
+            .map(|r| (r.file.as_path(), &r.status))
+            .collect()
+    }
+
+    #[test]
+    fn audit() {
+        let sha256 = |file, data| vec![digest(HashAlgorithm::Sha256, file, data)];
+        let known: Vec<_> = [
+            sha256("a.txt", b"a"),
+            sha256("b.txt", b"b"),
+            sha256("c.txt", b"c"),
+            sha256("d.txt", b"d"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let checksums = [
+            sha256("a.txt", b"a"),
+            sha256("e.txt", b"b"),
+            sha256("c.txt", b"changed"),
+        ];
+        let shared = Audit::shared_algorithms(&known, &[HashAlgorithm::Sha256.into()]).unwrap();
+
+        let results = Audit::check(&known, &shared, &checksums, &[1, 1, 7]);
+        assert_eq!(
+            statuses(&results),
+            [
+                (Path::new("a.txt"), &AuditStatus::Matched),
+                (
+                    Path::new("e.txt"),
+                    &AuditStatus::Moved(PathBuf::from("b.txt"))
+                ),
+                (Path::new("c.txt"), &AuditStatus::New),
+                (Path::new("c.txt"), &AuditStatus::Missing),
+                (Path::new("d.txt"), &AuditStatus::Missing),
+            ]
+        );
+        assert!(results[1].output().ends_with("MOVED (from b.txt)"));
+    }
+
+    #[test]
+    fn audit_shared_algorithms() {
+        // The known files have SHA-256 and SHA-512, and the size from hashdeep.
+        let known: Vec<_> = [("a.txt", b"a"), ("b.txt", b"b")]
+            .into_iter()
+            .flat_map(|(file, data)| {
+                [
+                    digest(HashAlgorithm::Sha256, file, data),
+                    digest(HashAlgorithm::Sha512, file, data),
+                ]
+            })
+            .map(|c| Checksum { size: Some(1), ..c })
+            .collect();
+        let checksums = [
+            vec![digest(HashAlgorithm::Sha256, "a.txt", b"a")],
+            vec![digest(HashAlgorithm::Sha256, "b.txt", b"b")],
+        ];
+        let algorithms = [HashAlgorithm::Sha256.into()];
+        let shared = Audit::shared_algorithms(&known, &algorithms).unwrap();
+        assert_eq!(shared, algorithms);
+
+        // The size of b.txt differs from the known size.
+        let results = Audit::check(&known, &shared, &checksums, &[1, 2]);
+        assert_eq!(
+            statuses(&results),
+            [
+                (Path::new("a.txt"), &AuditStatus::Matched),
+                (Path::new("b.txt"), &AuditStatus::New),
+                (Path::new("b.txt"), &AuditStatus::Missing),
+            ]
+        );
+
+        assert!(Audit::shared_algorithms(&known, &[HashAlgorithm::Sha384.into()]).is_err());
+    }
+}
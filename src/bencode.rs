@@ -0,0 +1,277 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, ensure, Context, Result};
+
+/// The maximum depth of nested lists and dictionaries.
+const MAX_DEPTH: usize = 64;
+
+/// A value of bencode, the encoding used by BitTorrent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Value>),
+    Dict(BTreeMap<Vec<u8>, Value>),
+}
+
+impl Value {
+    /// Encode the value.
+    ///
+    /// The keys of a dictionary are sorted as required by the specification.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode_to(&mut buf);
+
+        buf
+    }
+
+    fn encode_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            Self::Integer(i) => buf.extend(format!("i{}e", i).as_bytes()),
+            Self::Bytes(bytes) => {
+                buf.extend(format!("{}:", bytes.len()).as_bytes());
+                buf.extend(bytes);
+            }
+            Self::List(list) => {
+                buf.push(b'l');
+                for value in list {
+                    value.encode_to(buf);
+                }
+                buf.push(b'e');
+            }
+            Self::Dict(dict) => {
+                buf.push(b'd');
+                for (key, value) in dict {
+                    buf.extend(format!("{}:", key.len()).as_bytes());
+                    buf.extend(key);
+                    value.encode_to(buf);
+                }
+                buf.push(b'e');
+            }
+        }
+    }
+
+    /// Decode the value.
+    ///
+    /// Returns an error if the data is not a single valid value.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut decoder = Decoder { data, position: 0 };
+        let value = decoder.value(0)?;
+        ensure!(
+            decoder.position == data.len(),
+            "Trailing data after the bencoded value"
+        );
+
+        Ok(value)
+    }
+
+    /// Get the value of the key if the value is a dictionary.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Dict(dict) => dict.get(key.as_bytes()),
+            _ => None,
+        }
+    }
+
+    pub const fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "sha-1")]
+    pub fn as_list(&self) -> Option<&[Self]> {
+        match self {
+            Self::List(list) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub const fn as_dict(&self) -> Option<&BTreeMap<Vec<u8>, Self>> {
+        match self {
+            Self::Dict(dict) => Some(dict),
+            _ => None,
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Self::Integer(i)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::Bytes(s.as_bytes().to_vec())
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(list: Vec<Self>) -> Self {
+        Self::List(list)
+    }
+}
+
+impl<K: Into<Vec<u8>>> FromIterator<(K, Value)> for Value {
+    fn from_iter<T: IntoIterator<Item = (K, Self)>>(iter: T) -> Self {
+        Self::Dict(iter.into_iter().map(|(k, v)| (k.into(), v)).collect())
+    }
+}
+
+/// A decoder of bencode.
+struct Decoder<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn value(&mut self, depth: usize) -> Result<Value> {
+        ensure!(depth < MAX_DEPTH, "The bencoded value is nested too deeply");
+
+        match self.peek()? {
+            b'i' => {
+                self.position += 1;
+                let i = self.until(b'e')?;
+                ensure!(
+                    !(i.starts_with(b"-0") || i.len() > 1 && i[0] == b'0'),
+                    "Invalid integer in the bencoded value"
+                );
+                let i = std::str::from_utf8(i)
+                    .ok()
+                    .and_then(|i| i.parse().ok())
+                    .context("Invalid integer in the bencoded value")?;
+
+                Ok(Value::Integer(i))
+            }
+            b'0'..=b'9' => self.bytes().map(Value::Bytes),
+            b'l' => {
+                self.position += 1;
+                let mut list = Vec::new();
+                while self.peek()? != b'e' {
+                    list.push(self.value(depth + 1)?);
+                }
+                self.position += 1;
+
+                Ok(Value::List(list))
+            }
+            b'd' => {
+                self.position += 1;
+                let mut dict = BTreeMap::new();
+                while self.peek()? != b'e' {
+                    let key = self.bytes()?;
+                    let value = self.value(depth + 1)?;
+                    ensure!(
+                        dict.insert(key, value).is_none(),
+                        "Duplicate key in the bencoded value"
+                    );
+                }
+                self.position += 1;
+
+                Ok(Value::Dict(dict))
+            }
+            byte => bail!("Unexpected byte in the bencoded value: {:#04x}", byte),
+        }
+    }
+
+    /// Decode a byte string.
+    fn bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.until(b':')?;
+        ensure!(
+            !len.is_empty()
+                && len.iter().all(u8::is_ascii_digit)
+                && !(len.len() > 1 && len[0] == b'0'),
+            "Invalid length of a byte string in the bencoded value"
+        );
+        let len: usize = std::str::from_utf8(len)
+            .ok()
+            .and_then(|l| l.parse().ok())
+            .context("Invalid length of a byte string in the bencoded value")?;
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.position..end))
+            .context("Unexpected end of the bencoded value")?
+            .to_vec();
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    /// Get the bytes until the delimiter, and skip the delimiter.
+    fn until(&mut self, delimiter: u8) -> Result<&'a [u8]> {
+        let rest = &self.data[self.position..];
+        let len = rest
+            .iter()
+            .position(|&b| b == delimiter)
+            .context("Unexpected end of the bencoded value")?;
+        self.position += len + 1;
+
+        Ok(&rest[..len])
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.data
+            .get(self.position)
+            .copied()
+            .context("Unexpected end of the bencoded value")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_value() {
+        let value: Value = [
+            ("spam", Value::from(vec![Value::from("a"), Value::from(-1)])),
+            ("cow", Value::from("moo")),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(value.encode(), b"d3:cow3:moo4:spaml1:ai-1eee");
+    }
+
+    #[test]
+    fn decode_value() {
+        let value = Value::decode(b"d3:cow3:moo4:spaml1:ai-1eee").unwrap();
+
+        assert_eq!(
+            value.get("cow").and_then(Value::as_bytes),
+            Some(&b"moo"[..])
+        );
+        assert_eq!(
+            value.get("spam"),
+            Some(&Value::from(vec![Value::from("a"), Value::from(-1)]))
+        );
+        assert_eq!(value.encode(), b"d3:cow3:moo4:spaml1:ai-1eee");
+
+        assert!(Value::decode(b"i03e").is_err());
+        assert!(Value::decode(b"i-0e").is_err());
+        assert!(Value::decode(b"4:spa").is_err());
+        assert!(Value::decode(b"le1:a").is_err());
+        assert!(Value::decode(b"d1:ai0e1:ai1ee").is_err());
+    }
+}
@@ -7,13 +7,13 @@
 use std::io;
 use std::path::{Path, PathBuf};
 
-use anyhow::{ensure, Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use clap::{crate_name, AppSettings, ArgEnum, IntoApp, Parser};
 use clap_complete::Shell;
 
 use crate::config::Config;
 use crate::long_version;
-use crate::value::{HashAlgorithm, Style};
+use crate::value::{Encoding, HashAlgorithm, Multibase, Style};
 
 #[derive(Parser)]
 #[clap(
@@ -26,8 +26,94 @@ use crate::value::{HashAlgorithm, Style};
 )]
 pub struct Opt {
     /// Specify hash algorithm.
-    #[clap(short = 'H', long, value_name = "NAME", arg_enum, ignore_case = true)]
-    pub hash_algorithm: Option<HashAlgorithm>,
+    ///
+    /// Multiple hash algorithms can be specified as a comma-separated list,
+    /// and the message digests of them are computed while reading the input
+    /// once.
+    #[clap(
+        short = 'H',
+        long,
+        value_name = "NAME",
+        arg_enum,
+        ignore_case = true,
+        use_delimiter = true
+    )]
+    pub hash_algorithm: Vec<HashAlgorithm>,
+
+    /// Use the contents of <FILE> as the key for keyed hashing (MAC).
+    ///
+    /// This is supported by BLAKE2b, BLAKE2s and BLAKE3. The key for BLAKE3
+    /// must be exactly 32 bytes.
+    #[clap(long, value_name = "FILE")]
+    pub key_file: Option<PathBuf>,
+
+    /// Compute HMAC with the specified hash algorithm.
+    ///
+    /// This is supported by all hash algorithms except the extendable-output
+    /// functions (BLAKE3, SHAKE, cSHAKE and KangarooTwelve). The key is read
+    /// from the file specified by `--key-file`.
+    #[clap(long, requires = "key-file")]
+    pub hmac: bool,
+
+    /// Output the message digest of <BITS> bits.
+    ///
+    /// This is supported by BLAKE2b (up to 512 bits), BLAKE2s (up to 256
+    /// bits) and the extendable-output functions (BLAKE3, SHAKE, cSHAKE and
+    /// KangarooTwelve). <BITS> must be a multiple of 8.
+    #[clap(short, long, value_name = "BITS", conflicts_with = "hmac")]
+    pub length: Option<usize>,
+
+    /// Use <STRING> as the customization string for cSHAKE and KangarooTwelve.
+    #[clap(long, value_name = "STRING")]
+    pub customization: Option<String>,
+
+    /// Compute the git object IDs instead of the message digests.
+    ///
+    /// A file is hashed as a blob object, and a directory is hashed as a tree
+    /// object. This is supported by SHA-1 and SHA-256, which are the object
+    /// formats of git.
+    #[clap(
+        long,
+        conflicts_with_all = &["hmac", "length", "key-file", "customization"]
+    )]
+    pub git: bool,
+
+    /// Compute the tree digests of directories.
+    ///
+    /// The tree digest is a Merkle digest which covers the names and the
+    /// contents of all the files in a directory. It can be computed with any
+    /// hash algorithm.
+    #[clap(long, conflicts_with_all = &["git", "recursive"])]
+    pub tree: bool,
+
+    /// Include the executable bit of the files in the tree digests.
+    #[clap(long, requires = "tree")]
+    pub executable: bool,
+
+    /// Compute the info-hashes of BitTorrent instead of the message digests.
+    ///
+    /// SHA-1 computes the info-hash of BitTorrent v1, and SHA-256 computes
+    /// that of BitTorrent v2. A directory is hashed as a multi-file torrent.
+    #[clap(
+        long,
+        conflicts_with_all = &["hmac", "length", "key-file", "customization", "git", "tree", "recursive"]
+    )]
+    pub torrent: bool,
+
+    /// Use <BYTES> as the length of a piece of the torrents.
+    ///
+    /// <BYTES> must be a power of two and at least 16384. Default is 262144.
+    #[clap(long, value_name = "BYTES", requires = "torrent")]
+    pub piece_length: Option<u64>,
+
+    /// Save the torrents in <DIR> as `.torrent` files.
+    #[clap(
+        long,
+        value_name = "DIR",
+        requires = "torrent",
+        conflicts_with = "check"
+    )]
+    pub export_torrent: Option<PathBuf>,
 
     /// List supported hash algorithms.
     #[clap(long)]
@@ -37,6 +123,18 @@ pub struct Opt {
     #[clap(short, long, requires = "input", conflicts_with_all = &["output", "style"])]
     pub check: bool,
 
+    /// Audit the inputs against the known checksums in <FILE>.
+    ///
+    /// The inputs are reported as matched, moved or new, and the known files
+    /// which are not found are reported as missing.
+    #[clap(
+        short,
+        long,
+        value_name = "FILE",
+        conflicts_with_all = &["check", "output", "style", "tag", "binary", "multihash", "cid", "multibase"]
+    )]
+    pub audit: Option<PathBuf>,
+
     /// Don't fail or report status for missing files.
     #[clap(long, requires = "check")]
     pub ignore_missing: bool,
@@ -83,6 +181,96 @@ pub struct Opt {
     )]
     pub style: Style,
 
+    /// Output the checksums in <TEMPLATE> instead of the style.
+    ///
+    /// The placeholders such as `%{sha256}` and `%p` are replaced with the
+    /// message digests and the attributes of each file. <TEMPLATE> can also
+    /// be the name of a format in the config file.
+    #[clap(
+        long,
+        value_name = "TEMPLATE",
+        conflicts_with_all = &["check", "style", "tag", "binary", "audit"]
+    )]
+    pub format: Option<String>,
+
+    /// Output the checksums in the BSD style, which is the same as `--style bsd`.
+    #[clap(long, conflicts_with_all = &["check", "style"])]
+    pub tag: bool,
+
+    /// Mark the files as read in binary mode with `*` in the GNU style.
+    ///
+    /// This is for compatibility with GNU coreutils, and the files are always
+    /// read in binary mode.
+    #[clap(short, long, conflicts_with = "check")]
+    pub binary: bool,
+
+    /// Output the multihashes instead of the message digests.
+    ///
+    /// The multihash is the message digest prefixed with the multicodec code
+    /// of the hash algorithm and the length of the message digest.
+    #[clap(
+        long,
+        group = "multiformat",
+        conflicts_with_all = &["check", "hmac", "key-file", "customization", "git", "tree", "torrent"]
+    )]
+    pub multihash: bool,
+
+    /// Output the CIDv1s of the inputs as raw binary instead of the message
+    /// digests.
+    #[clap(
+        long,
+        group = "multiformat",
+        conflicts_with_all = &["check", "hmac", "key-file", "customization", "git", "tree", "torrent"]
+    )]
+    pub cid: bool,
+
+    /// Encode the message digests in the multibase <BASE>.
+    ///
+    /// The multihashes and the CIDs are always encoded in the multibase, and
+    /// default is base32.
+    #[clap(long, value_name = "BASE", arg_enum, conflicts_with = "check")]
+    pub multibase: Option<Multibase>,
+
+    /// Encode the message digests in <ENCODING>.
+    ///
+    /// This applies to all the styles. When verifying, the message digests are
+    /// decoded in <ENCODING> instead of detecting the encoding.
+    #[clap(
+        long,
+        value_name = "ENCODING",
+        arg_enum,
+        conflicts_with_all = &["multihash", "cid", "multibase"]
+    )]
+    pub encoding: Option<Encoding>,
+
+    /// Export the full Merkle trees of TTH to <FILE> as JSON.
+    ///
+    /// The hashes of each level from the root to the leaves are output in
+    /// base32 for each input.
+    #[clap(long, value_name = "FILE", conflicts_with = "check")]
+    pub export_tree: Option<PathBuf>,
+
+    /// Compute the checksums of the files in directories recursively.
+    #[clap(short, long, conflicts_with = "check")]
+    pub recursive: bool,
+
+    /// Only compute or verify the checksums of the files matching <GLOB>.
+    ///
+    /// This option can be specified multiple times.
+    #[clap(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip the files matching <GLOB>.
+    ///
+    /// A file is also skipped if any of its parent directories matches
+    /// <GLOB>. This option can be specified multiple times.
+    #[clap(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Skip the files ignored by `.gitignore` and `.ignore` files.
+    #[clap(long)]
+    pub gitignore: bool,
+
     /// Specify the number of threads to use.
     ///
     /// If <NUM> is `0`, use as many threads as there are CPU cores.
@@ -98,7 +286,7 @@ pub struct Opt {
     pub speed: bool,
 
     /// Input from <FILE>.
-    #[clap(value_name = "FILE")]
+    #[clap(value_name = "FILE", parse(from_os_str))]
     pub input: Vec<PathBuf>,
 
     /// Generate shell completion.
@@ -113,15 +301,70 @@ pub struct Opt {
 impl Opt {
     /// Apply the config from the config file.
     pub fn apply_config(mut self) -> Result<Self> {
+        let mut is_named_format = bool::default();
         if let Some(path) = Config::path() {
             let config = Config::read(&path)?;
             let matches = Self::into_app().get_matches();
 
+            if let Some(format) = config.format {
+                if matches.occurrences_of("format") == 0
+                    && matches.occurrences_of("style") == 0
+                    && !self.tag
+                    && !self.binary
+                    && self.audit.is_none()
+                    && !self.check
+                {
+                    self.format = Some(format);
+                }
+            }
             if let Some(style) = config.style {
-                if matches.occurrences_of("style") == 0 {
+                // The GNU style was named `sfv` before the SFV style was added,
+                // so the old name is kept as an alias in the config file.
+                let style = if style.eq_ignore_ascii_case("sfv") {
+                    eprintln!(
+                        "RSHash: WARNING: {}: `style = \"sfv\"` is deprecated and selects the \
+                         GNU style, use `style = \"gnu\"` instead",
+                        path.display()
+                    );
+                    Style::Gnu
+                } else {
+                    Style::from_str(&style, true)
+                        .map_err(|_| anyhow!("{}: Unknown style: {}", path.display(), style))?
+                };
+                if matches.occurrences_of("style") == 0 && self.format.is_none() {
                     self.style = style;
                 }
             }
+            if let Some(formats) = config.formats {
+                if let Some(format) = self.format.as_ref().and_then(|f| formats.get(f)) {
+                    self.format = Some(format.clone());
+                    is_named_format = true;
+                }
+            }
+            if let Some(include) = config.include {
+                if matches.occurrences_of("include") == 0 {
+                    self.include = include;
+                }
+            }
+            if let Some(exclude) = config.exclude {
+                if matches.occurrences_of("exclude") == 0 {
+                    self.exclude = exclude;
+                }
+            }
+            if let Some(gitignore) = config.gitignore {
+                if matches.occurrences_of("gitignore") == 0 {
+                    self.gitignore = gitignore;
+                }
+            }
+        }
+        // A bare word without placeholders is taken as the name of a format,
+        // since it is unlikely to be a template.
+        if let Some(ref format) = self.format {
+            ensure!(
+                is_named_format || format.contains(|c: char| c == '%' || c.is_whitespace()),
+                "Unknown format: {}",
+                format
+            );
         }
 
         Ok(self)
@@ -4,6 +4,7 @@
 // Copyright (C) 2021 Shun Sakai
 //
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -12,11 +13,16 @@ use clap::crate_name;
 use directories::ProjectDirs;
 use serde::Deserialize;
 
-use crate::value::Style;
-
 #[derive(Deserialize)]
 pub struct Config {
-    pub style: Option<Style>,
+    /// The style of the checksums, which is parsed after deserializing to
+    /// handle the deprecated name.
+    pub style: Option<String>,
+    pub format: Option<String>,
+    pub formats: Option<BTreeMap<String, String>>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub gitignore: Option<bool>,
 }
 
 impl Config {
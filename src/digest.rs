@@ -4,245 +4,766 @@
 // Copyright (C) 2021 Shun Sakai
 //
 
+use std::io::{self, Read};
 use std::path::Path;
 
-use crate::value::{Checksum, HashAlgorithm};
-
-impl Checksum {
-    /// Compute message digest for the specified hash algorithm.
-    pub fn digest<P: AsRef<Path>, T: AsRef<[u8]>>(
-        algorithm: HashAlgorithm,
-        input: &(P, T),
-    ) -> Self {
-        let digest = match algorithm {
-            HashAlgorithm::Blake2b => {
-                use blake2::{Blake2b512, Digest};
+use anyhow::{anyhow, bail, ensure, Result};
+use digest::block_buffer::Lazy;
+use digest::core_api::{
+    Block, BlockSizeUser, Buffer, BufferKindUser, UpdateCore, VariableOutputCore,
+};
+use digest::typenum::{IsLess, Le, NonZero, U256};
+use digest::{Digest, ExtendableOutput, Mac, Output, Update, XofReader};
+use hmac::SimpleHmac;
+
+use crate::tth::Tth;
+use crate::value::{Algorithm, Checksum, HashAlgorithm, Params};
+
+/// The size of the buffer used for reading the input.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// CRC-64/NVME, which is not in the catalog of the `crc` crate.
+const CRC_64_NVME: crc::Algorithm<u64> = crc::Algorithm {
+    width: 64,
+    poly: 0xad93_d235_94c9_3659,
+    init: 0xffff_ffff_ffff_ffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffff_ffff_ffff_ffff,
+    check: 0xae8b_1486_0a79_9888,
+    residue: 0xf310_303b_2b6f_6e42,
+};
+
+static CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+static CRC32C: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+// The variant used by xz and Go, which is commonly called CRC-64-ECMA.
+static CRC64_ECMA: crc::Crc<u64> = crc::Crc::<u64>::new(&crc::CRC_64_XZ);
+static CRC64_NVME: crc::Crc<u64> = crc::Crc::<u64>::new(&CRC_64_NVME);
+
+/// A hasher which computes a message digest incrementally.
+pub trait Hasher: Send {
+    /// Process the input data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Retrieve the message digest.
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+}
 
-                Blake2b512::digest(input.1.as_ref()).as_slice().to_vec()
+/// Implement [`Hasher`] for the hash functions of the RustCrypto project and
+/// map them to the hash algorithms.
+macro_rules! digest_hashers {
+    ($($(#[$attr:meta])* $algorithm:ident => $hasher:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            impl Hasher for $hasher {
+                fn update(&mut self, data: &[u8]) {
+                    Digest::update(self, data);
+                }
+
+                fn finalize(self: Box<Self>) -> Vec<u8> {
+                    Digest::finalize(*self).to_vec()
+                }
             }
-            HashAlgorithm::Blake2s => {
-                use blake2::{Blake2s256, Digest};
-
-                Blake2s256::digest(input.1.as_ref()).as_slice().to_vec()
+        )*
+
+        impl HashAlgorithm {
+            /// Create a hasher of the hash function which implements the
+            /// traits of the `digest` crate.
+            fn digest_hasher(self) -> Option<Box<dyn Hasher>> {
+                match self {
+                    $(
+                        $(#[$attr])*
+                        Self::$algorithm => Some(Box::new(<$hasher>::new())),
+                    )*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
             }
-            HashAlgorithm::Blake3 => blake3::hash(input.1.as_ref()).as_bytes().to_vec(),
-            HashAlgorithm::Fsb160 => {
-                use fsb::{Digest, Fsb160};
 
-                Fsb160::digest(input.1.as_ref()).as_slice().to_vec()
+            /// Create a hasher which computes HMAC with the key.
+            fn hmac_hasher(self, key: &[u8]) -> Option<Box<dyn Hasher>> {
+                match self {
+                    $(
+                        $(#[$attr])*
+                        Self::$algorithm => Some(Box::new(
+                            SimpleHmac::<$hasher>::new_from_slice(key)
+                                .expect("HMAC should accept a key of any length"),
+                        )),
+                    )*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
             }
-            HashAlgorithm::Fsb224 => {
-                use fsb::{Digest, Fsb224};
+        }
+    };
+}
 
-                Fsb224::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Fsb256 => {
-                use fsb::{Digest, Fsb256};
+digest_hashers!(
+    #[cfg(feature = "ascon-hash")]
+    AsconHash256 => ascon_hash::AsconHash256,
+    #[cfg(feature = "belt-hash")]
+    BeltHash => belt_hash::BeltHash,
+    Blake2b => blake2::Blake2b512,
+    Blake2s => blake2::Blake2s256,
+    Fsb160 => fsb::Fsb160,
+    Fsb224 => fsb::Fsb224,
+    Fsb256 => fsb::Fsb256,
+    Fsb384 => fsb::Fsb384,
+    Fsb512 => fsb::Fsb512,
+    #[cfg(feature = "gost94")]
+    Gost => gost94::Gost94Test,
+    #[cfg(feature = "gost94")]
+    GostCryptoPro => gost94::Gost94CryptoPro,
+    Groestl224 => groestl::Groestl224,
+    Groestl256 => groestl::Groestl256,
+    Groestl384 => groestl::Groestl384,
+    Groestl512 => groestl::Groestl512,
+    #[cfg(feature = "jh")]
+    Jh224 => jh::Jh224,
+    #[cfg(feature = "jh")]
+    Jh256 => jh::Jh256,
+    #[cfg(feature = "jh")]
+    Jh384 => jh::Jh384,
+    #[cfg(feature = "jh")]
+    Jh512 => jh::Jh512,
+    Keccak224 => sha3::Keccak224,
+    Keccak256 => sha3::Keccak256,
+    Keccak384 => sha3::Keccak384,
+    Keccak512 => sha3::Keccak512,
+    #[cfg(feature = "md2")]
+    Md2 => md2::Md2,
+    #[cfg(feature = "md4")]
+    Md4 => md4::Md4,
+    #[cfg(feature = "md-5")]
+    Md5 => md5::Md5,
+    Ripemd160 => ripemd::Ripemd160,
+    Ripemd256 => ripemd::Ripemd256,
+    Ripemd320 => ripemd::Ripemd320,
+    #[cfg(feature = "sha-1")]
+    Sha1 => sha1::Sha1,
+    Sha224 => sha2::Sha224,
+    Sha256 => sha2::Sha256,
+    Sha384 => sha2::Sha384,
+    Sha512 => sha2::Sha512,
+    Sha512_224 => sha2::Sha512_224,
+    Sha512_256 => sha2::Sha512_256,
+    Sha3_224 => sha3::Sha3_224,
+    Sha3_256 => sha3::Sha3_256,
+    Sha3_384 => sha3::Sha3_384,
+    Sha3_512 => sha3::Sha3_512,
+    Shabal192 => shabal::Shabal192,
+    Shabal224 => shabal::Shabal224,
+    Shabal256 => shabal::Shabal256,
+    Shabal384 => shabal::Shabal384,
+    Shabal512 => shabal::Shabal512,
+    #[cfg(feature = "skein")]
+    Skein256 => skein::Skein256<skein::consts::U32>,
+    #[cfg(feature = "skein")]
+    Skein512 => skein::Skein512<skein::consts::U64>,
+    #[cfg(feature = "skein")]
+    Skein1024 => skein::Skein1024<skein::consts::U128>,
+    Sm3 => sm3::Sm3,
+    #[cfg(feature = "streebog")]
+    Streebog256 => streebog::Streebog256,
+    #[cfg(feature = "streebog")]
+    Streebog512 => streebog::Streebog512,
+    Tiger => tiger::Tiger,
+    Whirlpool => whirlpool::Whirlpool,
+);
+
+impl<D: Digest + BlockSizeUser + Send> Hasher for SimpleHmac<D> {
+    fn update(&mut self, data: &[u8]) {
+        Mac::update(self, data);
+    }
 
-                Fsb256::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Fsb384 => {
-                use fsb::{Digest, Fsb384};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        Mac::finalize(*self).into_bytes().to_vec()
+    }
+}
 
-                Fsb384::digest(input.1.as_ref()).as_slice().to_vec()
+macro_rules! impl_mac_hasher {
+    ($($mac:ty),* $(,)?) => {
+        $(
+            impl Hasher for $mac {
+                fn update(&mut self, data: &[u8]) {
+                    Mac::update(self, data);
+                }
+
+                fn finalize(self: Box<Self>) -> Vec<u8> {
+                    Mac::finalize(*self).into_bytes().to_vec()
+                }
             }
-            HashAlgorithm::Fsb512 => {
-                use fsb::{Digest, Fsb512};
+        )*
+    };
+}
 
-                Fsb512::digest(input.1.as_ref()).as_slice().to_vec()
+impl_mac_hasher!(blake2::Blake2bMac512, blake2::Blake2sMac256);
+
+/// Implement [`Hasher`] for the hash functions which depend on other versions
+/// of the `digest` crate, and map them to the hash algorithms.
+macro_rules! foreign_hashers {
+    ($($(#[$attr:meta])* $algorithm:ident => $hasher:ty as $digest:path),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            impl Hasher for $hasher {
+                fn update(&mut self, data: &[u8]) {
+                    <Self as $digest>::update(self, data);
+                }
+
+                fn finalize(self: Box<Self>) -> Vec<u8> {
+                    <Self as $digest>::finalize(*self).to_vec()
+                }
             }
-            #[cfg(feature = "gost94")]
-            HashAlgorithm::Gost => {
-                use gost94::{Digest, Gost94Test};
-
-                Gost94Test::digest(input.1.as_ref()).as_slice().to_vec()
+        )*
+
+        impl HashAlgorithm {
+            /// Create a hasher of the hash function which implements the
+            /// traits of other versions of the `digest` crate.
+            fn foreign_hasher(self) -> Option<Box<dyn Hasher>> {
+                match self {
+                    $(
+                        $(#[$attr])*
+                        Self::$algorithm => Some(Box::new(<$hasher as $digest>::new())),
+                    )*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
             }
-            #[cfg(feature = "gost94")]
-            HashAlgorithm::GostCryptoPro => {
-                use gost94::{Digest, Gost94CryptoPro};
+        }
+    };
+}
 
-                Gost94CryptoPro::digest(input.1.as_ref())
-                    .as_slice()
-                    .to_vec()
-            }
-            HashAlgorithm::Groestl224 => {
-                use groestl::{Digest, Groestl224};
+foreign_hashers!(
+    #[cfg(feature = "blake-hash")]
+    Blake224 => blake_hash::Blake224 as blake_hash::Digest,
+    #[cfg(feature = "blake-hash")]
+    Blake256 => blake_hash::Blake256 as blake_hash::Digest,
+    #[cfg(feature = "blake-hash")]
+    Blake384 => blake_hash::Blake384 as blake_hash::Digest,
+    #[cfg(feature = "blake-hash")]
+    Blake512 => blake_hash::Blake512 as blake_hash::Digest,
+    #[cfg(feature = "kupyna")]
+    Kupyna224 => kupyna::Kupyna224 as kupyna::Digest,
+    #[cfg(feature = "kupyna")]
+    Kupyna256 => kupyna::Kupyna256 as kupyna::Digest,
+    #[cfg(feature = "kupyna")]
+    Kupyna384 => kupyna::Kupyna384 as kupyna::Digest,
+    #[cfg(feature = "kupyna")]
+    Kupyna512 => kupyna::Kupyna512 as kupyna::Digest,
+);
+
+impl Hasher for blake3::Hasher {
+    fn update(&mut self, data: &[u8]) {
+        blake3::Hasher::update(self, data);
+    }
 
-                Groestl224::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Groestl256 => {
-                use groestl::{Digest, Groestl256};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        blake3::Hasher::finalize(&self).as_bytes().to_vec()
+    }
+}
 
-                Groestl256::digest(input.1.as_ref()).as_slice().to_vec()
+/// Implement [`Hasher`] for the CRCs. The checksum is output in big-endian
+/// order.
+macro_rules! impl_crc_hasher {
+    ($($width:ty),* $(,)?) => {
+        $(
+            impl Hasher for crc::Digest<'static, $width> {
+                fn update(&mut self, data: &[u8]) {
+                    <crc::Digest<'static, $width>>::update(self, data);
+                }
+
+                fn finalize(self: Box<Self>) -> Vec<u8> {
+                    <crc::Digest<'static, $width>>::finalize(*self)
+                        .to_be_bytes()
+                        .to_vec()
+                }
             }
-            HashAlgorithm::Groestl384 => {
-                use groestl::{Digest, Groestl384};
+        )*
+    };
+}
 
-                Groestl384::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Groestl512 => {
-                use groestl::{Digest, Groestl512};
+impl_crc_hasher!(u32, u64);
 
-                Groestl512::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Keccak224 => {
-                use sha3::{Digest, Keccak224};
+impl Hasher for adler::Adler32 {
+    fn update(&mut self, data: &[u8]) {
+        self.write_slice(data);
+    }
 
-                Keccak224::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Keccak256 => {
-                use sha3::{Digest, Keccak256};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.checksum().to_be_bytes().to_vec()
+    }
+}
 
-                Keccak256::digest(input.1.as_ref()).as_slice().to_vec()
+/// Implement [`Hasher`] for xxHash. The hash value is output in big-endian
+/// order, which is the canonical representation of xxHash.
+macro_rules! impl_xxhash_hasher {
+    ($($hasher:ty => $digest:ident),* $(,)?) => {
+        $(
+            impl Hasher for $hasher {
+                fn update(&mut self, data: &[u8]) {
+                    <$hasher>::update(self, data);
+                }
+
+                fn finalize(self: Box<Self>) -> Vec<u8> {
+                    self.$digest().to_be_bytes().to_vec()
+                }
             }
-            HashAlgorithm::Keccak384 => {
-                use sha3::{Digest, Keccak384};
+        )*
+    };
+}
 
-                Keccak384::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Keccak512 => {
-                use sha3::{Digest, Keccak512};
+impl_xxhash_hasher!(
+    xxhash_rust::xxh32::Xxh32 => digest,
+    xxhash_rust::xxh64::Xxh64 => digest,
+);
 
-                Keccak512::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            #[cfg(feature = "md2")]
-            HashAlgorithm::Md2 => {
-                use md2::{Digest, Md2};
+/// XXH3 with the 128-bit output.
+struct Xxh3_128(xxhash_rust::xxh3::Xxh3);
 
-                Md2::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            #[cfg(feature = "md4")]
-            HashAlgorithm::Md4 => {
-                use md4::{Digest, Md4};
+impl Hasher for xxhash_rust::xxh3::Xxh3 {
+    fn update(&mut self, data: &[u8]) {
+        xxhash_rust::xxh3::Xxh3::update(self, data);
+    }
 
-                Md4::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            #[cfg(feature = "md-5")]
-            HashAlgorithm::Md5 => {
-                use md5::{Digest, Md5};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.digest().to_be_bytes().to_vec()
+    }
+}
 
-                Md5::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Ripemd160 => {
-                use ripemd::{Digest, Ripemd160};
+impl Hasher for Xxh3_128 {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
 
-                Ripemd160::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Ripemd256 => {
-                use ripemd::{Digest, Ripemd256};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        self.0.digest128().to_be_bytes().to_vec()
+    }
+}
 
-                Ripemd256::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Ripemd320 => {
-                use ripemd::{Digest, Ripemd320};
+/// BLAKE2 with a variable output length.
+///
+/// Unlike `Blake2bVar` and `Blake2sVar`, this also supports keyed hashing.
+struct Blake2Var<C>
+where
+    C: BufferKindUser<BufferKind = Lazy>,
+    C::BlockSize: IsLess<U256>,
+    Le<C::BlockSize, U256>: NonZero,
+{
+    core: C,
+    buffer: Buffer<C>,
+    size: usize,
+}
 
-                Ripemd320::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            #[cfg(feature = "sha-1")]
-            HashAlgorithm::Sha1 => {
-                use sha1::{Digest, Sha1};
+impl<C> Blake2Var<C>
+where
+    C: BufferKindUser<BufferKind = Lazy>,
+    C::BlockSize: IsLess<U256>,
+    Le<C::BlockSize, U256>: NonZero,
+{
+    /// Create a new hasher with the core initialized by the key length and
+    /// the output size.
+    fn new(core: C, key: &[u8], size: usize) -> Self {
+        let buffer = if key.is_empty() {
+            Buffer::<C>::default()
+        } else {
+            // The key is padded with zeros to a block and processed as the
+            // first block.
+            let mut block = Block::<C>::default();
+            block[..key.len()].copy_from_slice(key);
+            Buffer::<C>::new(&block)
+        };
 
-                Sha1::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha224 => {
-                use sha2::{Digest, Sha224};
+        Self { core, buffer, size }
+    }
+}
 
-                Sha224::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha256 => {
-                use sha2::{Digest, Sha256};
+impl<C> Hasher for Blake2Var<C>
+where
+    C: BufferKindUser<BufferKind = Lazy> + UpdateCore + VariableOutputCore + Send,
+    C::BlockSize: IsLess<U256>,
+    Le<C::BlockSize, U256>: NonZero,
+{
+    fn update(&mut self, data: &[u8]) {
+        let Self { core, buffer, .. } = self;
+        buffer.digest_blocks(data, |blocks| core.update_blocks(blocks));
+    }
 
-                Sha256::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha384 => {
-                use sha2::{Digest, Sha384};
+    fn finalize(mut self: Box<Self>) -> Vec<u8> {
+        let mut output = Output::<C>::default();
+        self.core
+            .finalize_variable_core(&mut self.buffer, &mut output);
+        output[..self.size].to_vec()
+    }
+}
 
-                Sha384::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha512 => {
-                use sha2::{Digest, Sha512};
+/// BLAKE3 with an extendable output.
+struct Blake3Xof {
+    hasher: blake3::Hasher,
+    size: usize,
+}
 
-                Sha512::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha3_224 => {
-                use sha3::{Digest, Sha3_224};
+impl Hasher for Blake3Xof {
+    fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
 
-                Sha3_224::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha3_256 => {
-                use sha3::{Digest, Sha3_256};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let mut output = vec![u8::default(); self.size];
+        self.hasher.finalize_xof().fill(&mut output);
+        output
+    }
+}
 
-                Sha3_256::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha3_384 => {
-                use sha3::{Digest, Sha3_384};
+/// An extendable-output function (XOF) which implements the traits of the
+/// `digest` crate.
+struct Xof<H> {
+    hasher: H,
+    size: usize,
+}
 
-                Sha3_384::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Sha3_512 => {
-                use sha3::{Digest, Sha3_512};
+impl<H: Update + ExtendableOutput + Send> Hasher for Xof<H> {
+    fn update(&mut self, data: &[u8]) {
+        Update::update(&mut self.hasher, data);
+    }
 
-                Sha3_512::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Shabal192 => {
-                use shabal::{Digest, Shabal192};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let mut output = vec![u8::default(); self.size];
+        self.hasher.finalize_xof().read(&mut output);
+        output
+    }
+}
 
-                Shabal192::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Shabal224 => {
-                use shabal::{Digest, Shabal224};
+/// KangarooTwelve with a customization string.
+struct KangarooTwelve {
+    hasher: tiny_keccak::KangarooTwelve<Vec<u8>>,
+    size: usize,
+}
 
-                Shabal224::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Shabal256 => {
-                use shabal::{Digest, Shabal256};
+impl Hasher for KangarooTwelve {
+    fn update(&mut self, data: &[u8]) {
+        tiny_keccak::Hasher::update(&mut self.hasher, data);
+    }
 
-                Shabal256::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Shabal384 => {
-                use shabal::{Digest, Shabal384};
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let mut output = vec![u8::default(); self.size];
+        tiny_keccak::Hasher::finalize(self.hasher, &mut output);
+        output
+    }
+}
 
-                Shabal384::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            HashAlgorithm::Shabal512 => {
-                use shabal::{Digest, Shabal512};
+impl HashAlgorithm {
+    /// Return `true` if the hash algorithm is a cryptographic hash function.
+    ///
+    /// Checksums such as CRCs and fast hash functions such as xxHash are not
+    /// suitable for detecting intentional modifications.
+    pub const fn is_cryptographic(self) -> bool {
+        !matches!(
+            self,
+            Self::Adler32
+                | Self::Crc32
+                | Self::Crc32c
+                | Self::Crc64Ecma
+                | Self::Crc64Nvme
+                | Self::Xxh32
+                | Self::Xxh64
+                | Self::Xxh3_64
+                | Self::Xxh3_128
+        )
+    }
 
-                Shabal512::digest(input.1.as_ref()).as_slice().to_vec()
+    /// Get the default length of the message digest in bits, if the hash
+    /// algorithm supports a variable output length.
+    pub const fn default_length(self) -> Option<usize> {
+        match self {
+            Self::Blake2b | Self::CShake256 | Self::Shake256 => Some(512),
+            Self::Blake2s | Self::Blake3 | Self::CShake128 | Self::K12 | Self::Shake128 => {
+                Some(256)
             }
-            HashAlgorithm::Sm3 => {
-                use sm3::{Digest, Sm3};
+            #[cfg(feature = "ascon-hash")]
+            Self::AsconXof128 => Some(256),
+            _ => None,
+        }
+    }
 
-                Sm3::digest(input.1.as_ref()).as_slice().to_vec()
-            }
-            #[cfg(feature = "streebog")]
-            HashAlgorithm::Streebog256 => {
-                use streebog::{Digest, Streebog256};
+    /// Create a hasher for the hash algorithm.
+    ///
+    /// If a key is specified, the hasher computes a keyed hash (MAC). Returns
+    /// an error if the hash algorithm does not support keyed hashing or the
+    /// length of the key is invalid.
+    pub fn hasher(self, params: &Params) -> Result<Box<dyn Hasher>> {
+        if let Some(ref key) = params.key {
+            return self.keyed_hasher(key);
+        }
 
-                Streebog256::digest(input.1.as_ref()).as_slice().to_vec()
+        match self {
+            Self::Adler32 => Ok(Box::new(adler::Adler32::new())),
+            Self::Blake3 => Ok(Box::new(blake3::Hasher::new())),
+            Self::Crc32 => Ok(Box::new(CRC32.digest())),
+            Self::Crc32c => Ok(Box::new(CRC32C.digest())),
+            Self::Crc64Ecma => Ok(Box::new(CRC64_ECMA.digest())),
+            Self::Crc64Nvme => Ok(Box::new(CRC64_NVME.digest())),
+            Self::Tth => Ok(Box::new(Tth::default())),
+            #[cfg(feature = "sha-1")]
+            Self::Aich => Ok(Box::new(crate::ed2k::Aich::default())),
+            #[cfg(feature = "md4")]
+            Self::Ed2k => Ok(Box::new(crate::ed2k::Ed2k::default())),
+            Self::Xxh32 => Ok(Box::new(xxhash_rust::xxh32::Xxh32::new(0))),
+            Self::Xxh64 => Ok(Box::new(xxhash_rust::xxh64::Xxh64::new(0))),
+            Self::Xxh3_64 => Ok(Box::new(xxhash_rust::xxh3::Xxh3::new())),
+            Self::Xxh3_128 => Ok(Box::new(Xxh3_128(xxhash_rust::xxh3::Xxh3::new()))),
+            Self::CShake128 | Self::CShake256 | Self::K12 | Self::Shake128 | Self::Shake256 => {
+                let length = self
+                    .default_length()
+                    .expect("XOF should have the default length");
+                self.variable_hasher(length / 8, params)
+            }
+            #[cfg(feature = "ascon-hash")]
+            Self::AsconXof128 => {
+                let length = self
+                    .default_length()
+                    .expect("XOF should have the default length");
+                self.variable_hasher(length / 8, params)
             }
-            #[cfg(feature = "streebog")]
-            HashAlgorithm::Streebog512 => {
-                use streebog::{Digest, Streebog512};
+            _ => Ok(self
+                .digest_hasher()
+                .or_else(|| self.foreign_hasher())
+                .expect("Hash algorithm should be implemented")),
+        }
+    }
 
-                Streebog512::digest(input.1.as_ref()).as_slice().to_vec()
+    /// Create a hasher which computes a keyed hash with the key.
+    fn keyed_hasher(self, key: &[u8]) -> Result<Box<dyn Hasher>> {
+        match self {
+            Self::Blake2b => {
+                ensure!(
+                    (1..=64).contains(&key.len()),
+                    "The key for {} must be 1 to 64 bytes",
+                    self
+                );
+
+                Ok(Box::new(
+                    <blake2::Blake2bMac512 as Mac>::new_from_slice(key)
+                        .expect("Key length should be valid"),
+                ))
+            }
+            Self::Blake2s => {
+                ensure!(
+                    (1..=32).contains(&key.len()),
+                    "The key for {} must be 1 to 32 bytes",
+                    self
+                );
+
+                Ok(Box::new(
+                    <blake2::Blake2sMac256 as Mac>::new_from_slice(key)
+                        .expect("Key length should be valid"),
+                ))
             }
-            HashAlgorithm::Tiger => {
-                use tiger::{digest::Digest, Tiger};
+            Self::Blake3 => {
+                let key = key
+                    .try_into()
+                    .map_err(|_| anyhow!("The key for {} must be 32 bytes", self))?;
 
-                Tiger::digest(input.1.as_ref()).as_slice().to_vec()
+                Ok(Box::new(blake3::Hasher::new_keyed(key)))
             }
-            HashAlgorithm::Whirlpool => {
-                use whirlpool::{Digest, Whirlpool};
+            _ => bail!("{} does not support keyed hashing", self),
+        }
+    }
 
-                Whirlpool::digest(input.1.as_ref()).as_slice().to_vec()
+    /// Create a hasher which outputs the message digest of `size` bytes.
+    fn variable_hasher(self, size: usize, params: &Params) -> Result<Box<dyn Hasher>> {
+        let customization = params.customization.clone().unwrap_or_default();
+
+        match (self, params.key.as_deref()) {
+            #[cfg(feature = "ascon-hash")]
+            (Self::AsconXof128, None) => Ok(Box::new(Xof {
+                hasher: ascon_hash::AsconXof128::default(),
+                size,
+            })),
+            #[cfg(feature = "ascon-hash")]
+            (Self::AsconXof128, Some(_)) => bail!("{} does not support keyed hashing", self),
+            (Self::Blake2b, key) => {
+                // An empty key is rejected, since it is the same as no key.
+                ensure!(
+                    key.map_or(true, |k| (1..=64).contains(&k.len())),
+                    "The key for {} must be 1 to 64 bytes",
+                    self
+                );
+                let key = key.unwrap_or_default();
+                let core = blake2::Blake2bVarCore::new_with_params(&[], &[], key.len(), size);
+
+                Ok(Box::new(Blake2Var::new(core, key, size)))
             }
-        };
+            (Self::Blake2s, key) => {
+                // An empty key is rejected, since it is the same as no key.
+                ensure!(
+                    key.map_or(true, |k| (1..=32).contains(&k.len())),
+                    "The key for {} must be 1 to 32 bytes",
+                    self
+                );
+                let key = key.unwrap_or_default();
+                let core = blake2::Blake2sVarCore::new_with_params(&[], &[], key.len(), size);
+
+                Ok(Box::new(Blake2Var::new(core, key, size)))
+            }
+            (Self::Blake3, None) => Ok(Box::new(Blake3Xof {
+                hasher: blake3::Hasher::new(),
+                size,
+            })),
+            (Self::Blake3, Some(key)) => {
+                let key = key
+                    .try_into()
+                    .map_err(|_| anyhow!("The key for {} must be 32 bytes", self))?;
+
+                Ok(Box::new(Blake3Xof {
+                    hasher: blake3::Hasher::new_keyed(key),
+                    size,
+                }))
+            }
+            (Self::CShake128, None) => Ok(Box::new(Xof {
+                hasher: sha3::CShake128::from_core(sha3::CShake128Core::new(&customization)),
+                size,
+            })),
+            (Self::CShake256, None) => Ok(Box::new(Xof {
+                hasher: sha3::CShake256::from_core(sha3::CShake256Core::new(&customization)),
+                size,
+            })),
+            (Self::K12, None) => Ok(Box::new(KangarooTwelve {
+                hasher: tiny_keccak::KangarooTwelve::new(customization),
+                size,
+            })),
+            (Self::Shake128, None) => Ok(Box::new(Xof {
+                hasher: sha3::Shake128::default(),
+                size,
+            })),
+            (Self::Shake256, None) => Ok(Box::new(Xof {
+                hasher: sha3::Shake256::default(),
+                size,
+            })),
+            (
+                Self::CShake128 | Self::CShake256 | Self::K12 | Self::Shake128 | Self::Shake256,
+                _,
+            ) => {
+                bail!("{} does not support keyed hashing", self)
+            }
+            _ => bail!("{} does not support a variable output length", self),
+        }
+    }
+}
+
+impl Algorithm {
+    /// Create a hasher for the algorithm.
+    ///
+    /// HMAC requires a key, and it is supported by the hash algorithms which
+    /// implement the traits of the `digest` crate.
+    pub fn hasher(self, params: &Params) -> Result<Box<dyn Hasher>> {
+        ensure!(
+            params.customization.is_none()
+                || matches!(
+                    self.hash,
+                    HashAlgorithm::CShake128 | HashAlgorithm::CShake256 | HashAlgorithm::K12
+                ),
+            "{} does not support a customization string",
+            self.hash
+        );
+
+        if let Some(length) = self.length {
+            return self.hash.variable_hasher(length / 8, params);
+        }
+        if !self.hmac {
+            return self.hash.hasher(params);
+        }
+
+        let key = params
+            .key
+            .as_ref()
+            .ok_or_else(|| anyhow!("{} requires a key", self))?;
+        self.hash
+            .hmac_hasher(key)
+            .ok_or_else(|| anyhow!("{} does not support HMAC", self.hash))
+    }
+
+    /// Get the length of the message digest in bytes.
+    pub fn digest_len(self) -> usize {
+        self.length.map_or_else(
+            || {
+                self.hash
+                    .hasher(&Params::default())
+                    .expect("Failed to create a hasher")
+                    .finalize()
+                    .len()
+            },
+            |length| length / 8,
+        )
+    }
+}
+
+impl Checksum {
+    /// Compute message digest for the specified hash algorithm.
+    #[cfg(test)]
+    pub fn digest<P: AsRef<Path>, T: AsRef<[u8]>>(
+        algorithm: impl Into<Algorithm>,
+        input: &(P, T),
+    ) -> Self {
+        let algorithm = algorithm.into();
+        let mut hasher = algorithm
+            .hasher(&Params::default())
+            .expect("Failed to create a hasher");
+        hasher.update(input.1.as_ref());
 
         Self {
             algorithm: Some(algorithm),
             file: input.0.as_ref().to_path_buf(),
-            digest,
+            digest: hasher.finalize(),
+            size: None,
+            alternatives: Vec::new(),
+        }
+    }
+
+    /// Compute message digests of the data read from the reader.
+    ///
+    /// The data is read in chunks and each chunk is passed to the hashers of
+    /// all the specified hash algorithms, so the input is read only once and
+    /// the memory usage does not depend on the size of the input. Returns the
+    /// checksums in the order of the hash algorithms and the number of bytes
+    /// read.
+    pub fn digest_reader(
+        algorithms: &[Algorithm],
+        params: &Params,
+        file: impl AsRef<Path>,
+        mut reader: impl Read,
+    ) -> Result<(Vec<Self>, u64)> {
+        let hashers: Result<Vec<_>> = algorithms.iter().map(|a| a.hasher(params)).collect();
+        let mut hashers = hashers?;
+        let mut buf = vec![u8::default(); BUFFER_SIZE];
+        let mut length = u64::default();
+
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    for hasher in &mut hashers {
+                        hasher.update(&buf[..n]);
+                    }
+                    length += u64::try_from(n).expect("Buffer size exceeds the limit");
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error.into()),
+            }
         }
+
+        let checksums = algorithms
+            .iter()
+            .zip(hashers)
+            .map(|(algorithm, hasher)| Self {
+                algorithm: Some(*algorithm),
+                file: file.as_ref().to_path_buf(),
+                digest: hasher.finalize(),
+                size: None,
+                alternatives: Vec::new(),
+            })
+            .collect();
+
+        Ok((checksums, length))
     }
 }
 
@@ -250,6 +771,147 @@ impl Checksum {
 mod tests {
     use super::*;
 
+    #[test]
+    fn digest_from_reader() {
+        let data = vec![u8::MAX; BUFFER_SIZE * 2 + 1];
+        let (checksums, length) = Checksum::digest_reader(
+            &[HashAlgorithm::Sha256.into(), HashAlgorithm::Blake3.into()],
+            &Params::default(),
+            "-",
+            data.as_slice(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            checksums[0].digest,
+            Checksum::digest(HashAlgorithm::Sha256, &("-", &data)).digest
+        );
+        assert_eq!(
+            checksums[1].digest,
+            Checksum::digest(HashAlgorithm::Blake3, &("-", &data)).digest
+        );
+        assert_eq!(length, u64::try_from(data.len()).unwrap());
+    }
+
+    #[test]
+    fn verify_adler32() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Adler32, &("-", b"Hello, world!")).digest,
+            [0x20, 0x5e, 0x04, 0x8a]
+        );
+    }
+
+    #[cfg(feature = "ascon-hash")]
+    #[test]
+    fn verify_ascon() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::AsconHash256, &("-", b"")).digest,
+            [
+                0x0b, 0x3b, 0xe5, 0x85, 0x0f, 0x2f, 0x6b, 0x98, 0xca, 0xf2, 0x9f, 0x8f, 0xde, 0xa8,
+                0x9b, 0x64, 0xa1, 0xfa, 0x70, 0xaa, 0x24, 0x9b, 0x8f, 0x83, 0x9b, 0xd5, 0x3b, 0xaa,
+                0x30, 0x4d, 0x92, 0xb2
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::AsconHash256, &("-", [0x00])).digest,
+            [
+                0x07, 0x28, 0x62, 0x10, 0x35, 0xaf, 0x3e, 0xd2, 0xbc, 0xa0, 0x3b, 0xf6, 0xfd, 0xe9,
+                0x00, 0xf9, 0x45, 0x6f, 0x53, 0x30, 0xe4, 0xb5, 0xee, 0x23, 0xe7, 0xf6, 0xa1, 0xe7,
+                0x02, 0x91, 0xbc, 0x80
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::AsconXof128, &("-", b"")).digest,
+            [
+                0x47, 0x3d, 0x5e, 0x61, 0x64, 0xf5, 0x8b, 0x39, 0xdf, 0xd8, 0x4a, 0xac, 0xdb, 0x8a,
+                0xe4, 0x2e, 0xc2, 0xd9, 0x1f, 0xed, 0x33, 0x38, 0x8e, 0xe0, 0xd9, 0x60, 0xd9, 0xb3,
+                0x99, 0x32, 0x95, 0xc6
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::AsconXof128, &("-", [0x00])).digest,
+            [
+                0x51, 0x43, 0x0e, 0x04, 0x38, 0xec, 0xdf, 0x64, 0x2b, 0x39, 0x36, 0x30, 0xd9, 0x77,
+                0x62, 0x5f, 0x5f, 0x33, 0x76, 0x56, 0xba, 0x58, 0xab, 0x1e, 0x96, 0x07, 0x84, 0xac,
+                0x32, 0xa1, 0x6e, 0x0d
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(
+                Algorithm::new(HashAlgorithm::AsconXof128, false, Some(128)).unwrap(),
+                &("-", b"")
+            )
+            .digest,
+            [
+                0x47, 0x3d, 0x5e, 0x61, 0x64, 0xf5, 0x8b, 0x39, 0xdf, 0xd8, 0x4a, 0xac, 0xdb, 0x8a,
+                0xe4, 0x2e
+            ]
+        );
+    }
+
+    #[cfg(feature = "belt-hash")]
+    #[test]
+    fn verify_belt_hash() {
+        assert_eq!(
+            Checksum::digest(
+                HashAlgorithm::BeltHash,
+                &(
+                    "-",
+                    [
+                        0xb1, 0x94, 0xba, 0xc8, 0x0a, 0x08, 0xf5, 0x3b, 0x36, 0x6d, 0x00, 0x8e,
+                        0x58
+                    ]
+                )
+            )
+            .digest,
+            [
+                0xab, 0xef, 0x97, 0x25, 0xd4, 0xc5, 0xa8, 0x35, 0x97, 0xa3, 0x67, 0xd1, 0x44, 0x94,
+                0xcc, 0x25, 0x42, 0xf2, 0x0f, 0x65, 0x9d, 0xdf, 0xec, 0xc9, 0x61, 0xa3, 0xec, 0x55,
+                0x0c, 0xba, 0x8c, 0x75
+            ]
+        );
+    }
+
+    #[cfg(feature = "blake-hash")]
+    #[test]
+    fn verify_blake() {
+        // The examples of one zero byte in the BLAKE paper.
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Blake224, &("-", [0x00])).digest,
+            [
+                0x45, 0x04, 0xcb, 0x03, 0x14, 0xfb, 0x2a, 0x4f, 0x7a, 0x69, 0x2e, 0x69, 0x6e, 0x48,
+                0x79, 0x12, 0xfe, 0x3f, 0x24, 0x68, 0xfe, 0x31, 0x2c, 0x73, 0xa5, 0x27, 0x8e, 0xc5
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Blake384, &("-", [0x00])).digest,
+            [
+                0x10, 0x28, 0x1f, 0x67, 0xe1, 0x35, 0xe9, 0x0a, 0xe8, 0xe8, 0x82, 0x25, 0x1a, 0x35,
+                0x55, 0x10, 0xa7, 0x19, 0x36, 0x7a, 0xd7, 0x02, 0x27, 0xb1, 0x37, 0x34, 0x3e, 0x1b,
+                0xc1, 0x22, 0x01, 0x5c, 0x29, 0x39, 0x1e, 0x85, 0x45, 0xb5, 0x27, 0x2d, 0x13, 0xa7,
+                0xc2, 0x87, 0x9d, 0xa3, 0xd8, 0x07
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Blake256, &("-", b"")).digest,
+            [
+                0x71, 0x6f, 0x6e, 0x86, 0x3f, 0x74, 0x4b, 0x9a, 0xc2, 0x2c, 0x97, 0xec, 0x7b, 0x76,
+                0xea, 0x5f, 0x59, 0x08, 0xbc, 0x5b, 0x2f, 0x67, 0xc6, 0x15, 0x10, 0xbf, 0xc4, 0x75,
+                0x13, 0x84, 0xea, 0x7a
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Blake512, &("-", b"")).digest,
+            [
+                0xa8, 0xcf, 0xbb, 0xd7, 0x37, 0x26, 0x06, 0x2d, 0xf0, 0xc6, 0x86, 0x4d, 0xda, 0x65,
+                0xde, 0xfe, 0x58, 0xef, 0x0c, 0xc5, 0x2a, 0x56, 0x25, 0x09, 0x0f, 0xa1, 0x76, 0x01,
+                0xe1, 0xee, 0xcd, 0x1b, 0x62, 0x8e, 0x94, 0xf3, 0x96, 0xae, 0x40, 0x2a, 0x00, 0xac,
+                0xc9, 0xea, 0xb7, 0x7b, 0x4d, 0x4c, 0x2e, 0x85, 0x2a, 0xaa, 0xa2, 0x5a, 0x63, 0x6d,
+                0x80, 0xaf, 0x3f, 0xc7, 0x91, 0x3e, 0xf5, 0xb8
+            ]
+        );
+    }
+
     #[test]
     fn verify_blake2() {
         assert_eq!(
@@ -284,6 +946,313 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verify_keyed_blake2() {
+        let params = Params {
+            key: Some((0..64).collect()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Checksum::digest_reader(
+                &[HashAlgorithm::Blake2b.into()],
+                &params,
+                "-",
+                b"".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0x10, 0xeb, 0xb6, 0x77, 0x00, 0xb1, 0x86, 0x8e, 0xfb, 0x44, 0x17, 0x98, 0x7a, 0xcf,
+                0x46, 0x90, 0xae, 0x9d, 0x97, 0x2f, 0xb7, 0xa5, 0x90, 0xc2, 0xf0, 0x28, 0x71, 0x79,
+                0x9a, 0xaa, 0x47, 0x86, 0xb5, 0xe9, 0x96, 0xe8, 0xf0, 0xf4, 0xeb, 0x98, 0x1f, 0xc2,
+                0x14, 0xb0, 0x05, 0xf4, 0x2d, 0x2f, 0xf4, 0x23, 0x34, 0x99, 0x39, 0x16, 0x53, 0xdf,
+                0x7a, 0xef, 0xcb, 0xc1, 0x3f, 0xc5, 0x15, 0x68
+            ]
+        );
+        let params = Params {
+            key: Some((0..32).collect()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Checksum::digest_reader(
+                &[HashAlgorithm::Blake2s.into()],
+                &params,
+                "-",
+                b"Hello, world!".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0x31, 0xd0, 0x0f, 0x16, 0x07, 0xcc, 0x65, 0xa3, 0xfd, 0x2f, 0x42, 0x58, 0x85, 0x1b,
+                0xad, 0xce, 0xac, 0xa8, 0x1f, 0x6a, 0xf9, 0xb9, 0x8f, 0x49, 0x0f, 0x4c, 0x6f, 0x55,
+                0xeb, 0x22, 0xdd, 0x58
+            ]
+        );
+
+        // An empty key must not compute the unkeyed message digest.
+        let params = Params {
+            key: Some(Vec::new()),
+            ..Default::default()
+        };
+        for hash in [HashAlgorithm::Blake2b, HashAlgorithm::Blake2s] {
+            for length in [None, Some(128)] {
+                let algorithm = Algorithm::new(hash, false, length).unwrap();
+                assert!(
+                    Checksum::digest_reader(&[algorithm], &params, "-", b"".as_slice()).is_err()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn verify_keyed_blake3() {
+        let params = Params {
+            key: Some(b"whats the Elvish word for friend".to_vec()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Checksum::digest_reader(
+                &[HashAlgorithm::Blake3.into()],
+                &params,
+                "-",
+                b"".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0x92, 0xb2, 0xb7, 0x56, 0x04, 0xed, 0x3c, 0x76, 0x1f, 0x9d, 0x6f, 0x62, 0x39, 0x2c,
+                0x8a, 0x92, 0x27, 0xad, 0x0e, 0xa3, 0xf0, 0x95, 0x73, 0xe7, 0x83, 0xf1, 0x49, 0x8a,
+                0x4e, 0xd6, 0x0d, 0x26
+            ]
+        );
+    }
+
+    #[test]
+    fn verify_variable_length() {
+        assert_eq!(
+            Checksum::digest_reader(
+                &[Algorithm::new(HashAlgorithm::Blake2b, false, Some(256)).unwrap()],
+                &Params::default(),
+                "-",
+                b"Hello, world!".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0xb5, 0xda, 0x44, 0x1c, 0xfe, 0x72, 0xae, 0x04, 0x2e, 0xf4, 0xd2, 0xb1, 0x77, 0x42,
+                0x90, 0x7f, 0x67, 0x5d, 0xe4, 0xda, 0x57, 0x46, 0x2d, 0x4c, 0x36, 0x09, 0xc2, 0xe2,
+                0xed, 0x75, 0x59, 0x70
+            ]
+        );
+        assert_eq!(
+            Checksum::digest_reader(
+                &[Algorithm::new(HashAlgorithm::Blake3, false, Some(512)).unwrap()],
+                &Params::default(),
+                "-",
+                b"Hello, world!".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0xed, 0xe5, 0xc0, 0xb1, 0x0f, 0x2e, 0xc4, 0x97, 0x9c, 0x69, 0xb5, 0x2f, 0x61, 0xe4,
+                0x2f, 0xf5, 0xb4, 0x13, 0x51, 0x9c, 0xe0, 0x9b, 0xe0, 0xf1, 0x4d, 0x09, 0x8d, 0xcf,
+                0xe5, 0xf6, 0xf9, 0x8d, 0x7d, 0x46, 0x33, 0x78, 0x12, 0xf8, 0x73, 0x27, 0xad, 0x71,
+                0x08, 0xfb, 0xaa, 0x95, 0x5f, 0x00, 0x9a, 0xfa, 0x49, 0x5c, 0xa8, 0xc0, 0x5c, 0x33,
+                0xeb, 0x05, 0x00, 0x02, 0xac, 0x9b, 0x99, 0xbc
+            ]
+        );
+        let params = Params {
+            key: Some(b"Jefe".to_vec()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Checksum::digest_reader(
+                &[Algorithm::new(HashAlgorithm::Blake2s, false, Some(160)).unwrap()],
+                &params,
+                "-",
+                b"Hello, world!".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0x14, 0xed, 0xad, 0x28, 0xd4, 0xfb, 0x8c, 0x53, 0x4a, 0x99, 0x83, 0x3f, 0x3a, 0x23,
+                0x46, 0xec, 0xfd, 0x06, 0xa4, 0xec
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert!(Algorithm::new(HashAlgorithm::Blake2b, false, Some(520)).is_err());
+        assert!(Algorithm::new(HashAlgorithm::Blake2s, false, Some(100)).is_err());
+        assert!(Algorithm::new(HashAlgorithm::Blake3, true, Some(512)).is_err());
+        assert!(Algorithm::new(HashAlgorithm::Sha256, false, Some(256)).is_err());
+        assert!(Algorithm::new(HashAlgorithm::Shake128, false, Some(65536)).is_ok());
+        assert!(Algorithm::new(HashAlgorithm::Shake128, false, Some(65544)).is_err());
+        assert!(Algorithm::new(HashAlgorithm::Blake3, false, Some(1_000_000_000)).is_err());
+    }
+
+    #[test]
+    fn verify_hmac() {
+        let params = Params {
+            key: Some(b"Jefe".to_vec()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Checksum::digest_reader(
+                &[Algorithm::new(HashAlgorithm::Sha256, true, None).unwrap()],
+                &params,
+                "-",
+                b"what do ya want for nothing?".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95,
+                0x75, 0xc7, 0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9,
+                0x64, 0xec, 0x38, 0x43
+            ]
+        );
+        assert_eq!(
+            Checksum::digest_reader(
+                &[Algorithm::new(HashAlgorithm::Sha512, true, None).unwrap()],
+                &params,
+                "-",
+                b"what do ya want for nothing?".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0x16, 0x4b, 0x7a, 0x7b, 0xfc, 0xf8, 0x19, 0xe2, 0xe3, 0x95, 0xfb, 0xe7, 0x3b, 0x56,
+                0xe0, 0xa3, 0x87, 0xbd, 0x64, 0x22, 0x2e, 0x83, 0x1f, 0xd6, 0x10, 0x27, 0x0c, 0xd7,
+                0xea, 0x25, 0x05, 0x54, 0x97, 0x58, 0xbf, 0x75, 0xc0, 0x5a, 0x99, 0x4a, 0x6d, 0x03,
+                0x4f, 0x65, 0xf8, 0xf0, 0xe6, 0xfd, 0xca, 0xea, 0xb1, 0xa3, 0x4d, 0x4a, 0x6b, 0x4b,
+                0x63, 0x6e, 0x07, 0x0a, 0x38, 0xbc, 0xe7, 0x37
+            ]
+        );
+        assert_eq!(
+            Checksum::digest_reader(
+                &[Algorithm::new(HashAlgorithm::Sha3_256, true, None).unwrap()],
+                &params,
+                "-",
+                b"what do ya want for nothing?".as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0xc7, 0xd4, 0x07, 0x2e, 0x78, 0x88, 0x77, 0xae, 0x35, 0x96, 0xbb, 0xb0, 0xda, 0x73,
+                0xb8, 0x87, 0xc9, 0x17, 0x1f, 0x93, 0x09, 0x5b, 0x29, 0x4a, 0xe8, 0x57, 0xfb, 0xe2,
+                0x64, 0x5e, 0x1b, 0xa5
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_hmac() {
+        let algorithm = Algorithm::new(HashAlgorithm::Sha256, true, None).unwrap();
+        assert!(algorithm.hasher(&Params::default()).is_err());
+        let algorithm = Algorithm::new(HashAlgorithm::Blake3, true, None).unwrap();
+        let params = Params {
+            key: Some(b"Jefe".to_vec()),
+            ..Default::default()
+        };
+        assert!(algorithm.hasher(&params).is_err());
+    }
+
+    #[test]
+    fn invalid_key() {
+        let params = Params {
+            key: Some(vec![u8::default(); 65]),
+            ..Default::default()
+        };
+        assert!(HashAlgorithm::Blake2b.hasher(&params).is_err());
+        assert!(HashAlgorithm::Blake3.hasher(&params).is_err());
+        assert!(HashAlgorithm::Sha256.hasher(&params).is_err());
+    }
+
+    #[test]
+    fn verify_crc() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Crc32, &("-", b"Hello, world!")).digest,
+            [0xeb, 0xe6, 0xc6, 0xe6]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Crc32c, &("-", b"Hello, world!")).digest,
+            [0xc8, 0xa1, 0x06, 0xe5]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Crc64Ecma, &("-", b"Hello, world!")).digest,
+            [0x8e, 0x59, 0xe1, 0x43, 0x66, 0x58, 0x77, 0xc4]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Crc64Nvme, &("-", b"Hello, world!")).digest,
+            [0x9f, 0x78, 0x55, 0x69, 0x00, 0x1a, 0x3d, 0x34]
+        );
+    }
+
+    #[test]
+    fn invalid_customization() {
+        let params = Params {
+            customization: Some(b"Email Signature".to_vec()),
+            ..Default::default()
+        };
+        assert!(Algorithm::from(HashAlgorithm::Shake128)
+            .hasher(&params)
+            .is_err());
+        assert!(Algorithm::from(HashAlgorithm::K12).hasher(&params).is_ok());
+    }
+
+    #[test]
+    fn verify_cshake() {
+        let params = Params {
+            customization: Some(b"Email Signature".to_vec()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Checksum::digest_reader(
+                &[HashAlgorithm::CShake128.into()],
+                &params,
+                "-",
+                [0x00, 0x01, 0x02, 0x03].as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0xc1, 0xc3, 0x69, 0x25, 0xb6, 0x40, 0x9a, 0x04, 0xf1, 0xb5, 0x04, 0xfc, 0xbc, 0xa9,
+                0xd8, 0x2b, 0x40, 0x17, 0x27, 0x7c, 0xb5, 0xed, 0x2b, 0x20, 0x65, 0xfc, 0x1d, 0x38,
+                0x14, 0xd5, 0xaa, 0xf5
+            ]
+        );
+        assert_eq!(
+            Checksum::digest_reader(
+                &[HashAlgorithm::CShake256.into()],
+                &params,
+                "-",
+                [0x00, 0x01, 0x02, 0x03].as_slice()
+            )
+            .unwrap()
+            .0[0]
+                .digest,
+            [
+                0xd0, 0x08, 0x82, 0x8e, 0x2b, 0x80, 0xac, 0x9d, 0x22, 0x18, 0xff, 0xee, 0x1d, 0x07,
+                0x0c, 0x48, 0xb8, 0xe4, 0xc8, 0x7b, 0xff, 0x32, 0xc9, 0x69, 0x9d, 0x5b, 0x68, 0x96,
+                0xee, 0xe0, 0xed, 0xd1, 0x64, 0x02, 0x0e, 0x2b, 0xe0, 0x56, 0x08, 0x58, 0xd9, 0xc0,
+                0x0c, 0x03, 0x7e, 0x34, 0xa9, 0x69, 0x37, 0xc5, 0x61, 0xa7, 0x4c, 0x41, 0x2b, 0xb4,
+                0xc7, 0x46, 0x46, 0x95, 0x27, 0x28, 0x1c, 0x8c
+            ]
+        );
+    }
+
     #[test]
     fn verify_fsb() {
         assert_eq!(
@@ -388,6 +1357,65 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "jh")]
+    #[test]
+    fn verify_jh() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Jh256, &("-", b"")).digest,
+            [
+                0x46, 0xe6, 0x46, 0x19, 0xc1, 0x8b, 0xb0, 0xa9, 0x2a, 0x5e, 0x87, 0x18, 0x5a, 0x47,
+                0xee, 0xf8, 0x3c, 0xa7, 0x47, 0xb8, 0xfc, 0xc8, 0xe1, 0x41, 0x29, 0x21, 0x35, 0x7e,
+                0x32, 0x6d, 0xf4, 0x34
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Jh224, &("-", b"")).digest,
+            [
+                0x2c, 0x99, 0xdf, 0x88, 0x9b, 0x01, 0x93, 0x09, 0x05, 0x1c, 0x60, 0xfe, 0xcc, 0x2b,
+                0xd2, 0x85, 0xa7, 0x74, 0x94, 0x0e, 0x43, 0x17, 0x5b, 0x76, 0xb2, 0x62, 0x66, 0x30
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Jh384, &("-", b"")).digest,
+            [
+                0x2f, 0xe5, 0xf7, 0x1b, 0x1b, 0x32, 0x90, 0xd3, 0xc0, 0x17, 0xfb, 0x3c, 0x1a, 0x4d,
+                0x02, 0xa5, 0xcb, 0xeb, 0x03, 0xa0, 0x47, 0x64, 0x81, 0xe2, 0x50, 0x82, 0x43, 0x4a,
+                0x88, 0x19, 0x94, 0xb0, 0xff, 0x99, 0xe0, 0x78, 0xd2, 0xc1, 0x6b, 0x10, 0x5a, 0xd0,
+                0x69, 0xb5, 0x69, 0x31, 0x53, 0x28
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Jh512, &("-", b"")).digest,
+            [
+                0x90, 0xec, 0xf2, 0xf7, 0x6f, 0x9d, 0x2c, 0x80, 0x17, 0xd9, 0x79, 0xad, 0x5a, 0xb9,
+                0x6b, 0x87, 0xd5, 0x8f, 0xc8, 0xfc, 0x4b, 0x83, 0x06, 0x0f, 0x3f, 0x90, 0x07, 0x74,
+                0xfa, 0xa2, 0xc8, 0xfa, 0xbe, 0x69, 0xc5, 0xf4, 0xff, 0x1e, 0xc2, 0xb6, 0x1d, 0x6b,
+                0x31, 0x69, 0x41, 0xce, 0xde, 0xe1, 0x17, 0xfb, 0x04, 0xb1, 0xf4, 0xc5, 0xbc, 0x1b,
+                0x91, 0x9a, 0xe8, 0x41, 0xc5, 0x0e, 0xec, 0x4f
+            ]
+        );
+    }
+
+    #[test]
+    fn verify_k12() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::K12, &("-", b"")).digest,
+            [
+                0x1a, 0xc2, 0xd4, 0x50, 0xfc, 0x3b, 0x42, 0x05, 0xd1, 0x9d, 0xa7, 0xbf, 0xca, 0x1b,
+                0x37, 0x51, 0x3c, 0x08, 0x03, 0x57, 0x7a, 0xc7, 0x16, 0x7f, 0x06, 0xfe, 0x2c, 0xe1,
+                0xf0, 0xef, 0x39, 0xe5
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::K12, &("-", (0..17).collect::<Vec<u8>>())).digest,
+            [
+                0x6b, 0xf7, 0x5f, 0xa2, 0x23, 0x91, 0x98, 0xdb, 0x47, 0x72, 0xe3, 0x64, 0x78, 0xf8,
+                0xe1, 0x9b, 0x0f, 0x37, 0x12, 0x05, 0xf6, 0xa9, 0xa9, 0x3a, 0x27, 0x3f, 0x51, 0xdf,
+                0x37, 0x12, 0x28, 0x88
+            ]
+        );
+    }
+
     #[test]
     fn verify_keccak() {
         assert_eq!(
@@ -426,6 +1454,63 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "kupyna")]
+    #[test]
+    fn verify_kupyna() {
+        // Kupyna-224 is the last 224 bits of the state of Kupyna-256, so the
+        // example of Kupyna-256 is truncated.
+        assert_eq!(
+            Checksum::digest(
+                HashAlgorithm::Kupyna224,
+                &("-", (0..64).collect::<Vec<u8>>())
+            )
+            .digest,
+            [
+                0x1b, 0xe6, 0x90, 0x3b, 0x32, 0x4c, 0x4e, 0x27, 0x99, 0x0c, 0xb2, 0x4e, 0xf6, 0x9d,
+                0xd5, 0x8d, 0xbe, 0x84, 0x81, 0x3e, 0xe0, 0xa5, 0x2f, 0x66, 0x31, 0x23, 0x98, 0x75
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(
+                HashAlgorithm::Kupyna384,
+                &("-", (0..95).collect::<Vec<u8>>())
+            )
+            .digest,
+            [
+                0xd9, 0x02, 0x16, 0x92, 0xd8, 0x4e, 0x51, 0x75, 0x73, 0x56, 0x54, 0x84, 0x6b, 0xa7,
+                0x51, 0xe6, 0xd0, 0xed, 0x0f, 0xac, 0x36, 0xdf, 0xbc, 0x08, 0x41, 0x28, 0x7d, 0xcb,
+                0x0b, 0x55, 0x84, 0xc7, 0x50, 0x16, 0xc3, 0xde, 0xcc, 0x2a, 0x6e, 0x47, 0xc5, 0x0b,
+                0x2f, 0x38, 0x11, 0xe3, 0x51, 0xb8
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(
+                HashAlgorithm::Kupyna256,
+                &("-", (0..64).collect::<Vec<u8>>())
+            )
+            .digest,
+            [
+                0x08, 0xf4, 0xee, 0x6f, 0x1b, 0xe6, 0x90, 0x3b, 0x32, 0x4c, 0x4e, 0x27, 0x99, 0x0c,
+                0xb2, 0x4e, 0xf6, 0x9d, 0xd5, 0x8d, 0xbe, 0x84, 0x81, 0x3e, 0xe0, 0xa5, 0x2f, 0x66,
+                0x31, 0x23, 0x98, 0x75
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(
+                HashAlgorithm::Kupyna512,
+                &("-", (0..64).collect::<Vec<u8>>())
+            )
+            .digest,
+            [
+                0x38, 0x13, 0xe2, 0x10, 0x91, 0x18, 0xcd, 0xfb, 0x5a, 0x6d, 0x5e, 0x72, 0xf7, 0x20,
+                0x8d, 0xcc, 0xc8, 0x0a, 0x2d, 0xfb, 0x3a, 0xfd, 0xfb, 0x02, 0xf4, 0x69, 0x92, 0xb5,
+                0xed, 0xbe, 0x53, 0x6b, 0x35, 0x60, 0xdd, 0x1d, 0x7e, 0x29, 0xc6, 0xf5, 0x39, 0x78,
+                0xaf, 0x58, 0xb4, 0x44, 0xe3, 0x7b, 0xa6, 0x85, 0xc0, 0xdd, 0x91, 0x05, 0x33, 0xba,
+                0x5d, 0x78, 0xef, 0xff, 0xc1, 0x3d, 0xe6, 0x2a
+            ]
+        );
+    }
+
     #[cfg(feature = "md2")]
     #[test]
     fn verify_md2() {
@@ -537,6 +1622,21 @@ mod tests {
                 0xf5, 0x1f, 0x6e, 0x7a, 0x57, 0xf7, 0x94, 0x21
             ]
         );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Sha512_224, &("-", b"Hello, world!")).digest,
+            [
+                0x32, 0x62, 0x00, 0x68, 0xb8, 0x59, 0x66, 0x9b, 0x45, 0xb3, 0x10, 0x08, 0xe0, 0x8b,
+                0x73, 0x84, 0x64, 0x9a, 0xd2, 0xca, 0x3f, 0x51, 0x63, 0xa3, 0xa7, 0x1e, 0x57, 0x45
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Sha512_256, &("-", b"Hello, world!")).digest,
+            [
+                0x33, 0x0c, 0x72, 0x3f, 0x25, 0x26, 0x75, 0x87, 0xdb, 0x0b, 0x9f, 0x49, 0x34, 0x63,
+                0xe0, 0x17, 0x01, 0x12, 0x39, 0x16, 0x9c, 0xb5, 0x7a, 0x6d, 0xb2, 0x16, 0xc6, 0x37,
+                0x74, 0x36, 0x71, 0x15
+            ]
+        );
     }
 
     #[test]
@@ -622,6 +1722,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verify_shake() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Shake128, &("-", b"Hello, world!")).digest,
+            [
+                0xb5, 0xff, 0xd1, 0x13, 0xfa, 0x12, 0x7f, 0x4d, 0x9c, 0x7e, 0x48, 0x3c, 0xb5, 0x22,
+                0x64, 0xed, 0x41, 0x35, 0x54, 0xef, 0x89, 0x9c, 0x0c, 0xf7, 0xc1, 0xd7, 0x36, 0xdd,
+                0xb9, 0x33, 0x13, 0xa6
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Shake256, &("-", b"Hello, world!")).digest,
+            [
+                0xcf, 0x68, 0xa0, 0xd3, 0x88, 0x04, 0x7e, 0xd5, 0x88, 0xad, 0x72, 0xd3, 0x80, 0x8c,
+                0xf9, 0xa3, 0x24, 0x3f, 0x04, 0xd4, 0x90, 0x17, 0x48, 0xc7, 0x05, 0xfb, 0xf3, 0xa2,
+                0x7d, 0x95, 0x55, 0x42, 0xfd, 0x9d, 0x53, 0xaf, 0x53, 0xe8, 0x4c, 0x8a, 0xbd, 0x4f,
+                0xce, 0x6e, 0x22, 0x4a, 0xf9, 0xa0, 0xa9, 0xe7, 0xee, 0xa5, 0x57, 0x3a, 0x88, 0x6b,
+                0x1a, 0xf8, 0xc2, 0x9f, 0x98, 0x97, 0xc8, 0xb5
+            ]
+        );
+    }
+
+    #[cfg(feature = "skein")]
+    #[test]
+    fn verify_skein() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Skein256, &("-", b"")).digest,
+            [
+                0xc8, 0x87, 0x70, 0x87, 0xda, 0x56, 0xe0, 0x72, 0x87, 0x0d, 0xaa, 0x84, 0x3f, 0x17,
+                0x6e, 0x94, 0x53, 0x11, 0x59, 0x29, 0x09, 0x4c, 0x3a, 0x40, 0xc4, 0x63, 0xa1, 0x96,
+                0xc2, 0x9b, 0xf7, 0xba
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Skein512, &("-", b"")).digest,
+            [
+                0xbc, 0x5b, 0x4c, 0x50, 0x92, 0x55, 0x19, 0xc2, 0x90, 0xcc, 0x63, 0x42, 0x77, 0xae,
+                0x3d, 0x62, 0x57, 0x21, 0x23, 0x95, 0xcb, 0xa7, 0x33, 0xbb, 0xad, 0x37, 0xa4, 0xaf,
+                0x0f, 0xa0, 0x6a, 0xf4, 0x1f, 0xca, 0x79, 0x03, 0xd0, 0x65, 0x64, 0xfe, 0xa7, 0xa2,
+                0xd3, 0x73, 0x0d, 0xbd, 0xb8, 0x0c, 0x1f, 0x85, 0x56, 0x2d, 0xfc, 0xc0, 0x70, 0x33,
+                0x4e, 0xa4, 0xd1, 0xd9, 0xe7, 0x2c, 0xba, 0x7a
+            ]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Skein1024, &("-", b"")).digest,
+            [
+                0x0f, 0xff, 0x95, 0x63, 0xbb, 0x32, 0x79, 0x28, 0x92, 0x27, 0xac, 0x77, 0xd3, 0x19,
+                0xb6, 0xff, 0xf8, 0xd7, 0xe9, 0xf0, 0x9d, 0xa1, 0x24, 0x7b, 0x72, 0xa0, 0xa2, 0x65,
+                0xcd, 0x6d, 0x2a, 0x62, 0x64, 0x5a, 0xd5, 0x47, 0xed, 0x81, 0x93, 0xdb, 0x48, 0xcf,
+                0xf8, 0x47, 0xc0, 0x64, 0x94, 0xa0, 0x3f, 0x55, 0x66, 0x6d, 0x3b, 0x47, 0xeb, 0x4c,
+                0x20, 0x45, 0x6c, 0x93, 0x73, 0xc8, 0x62, 0x97, 0xd6, 0x30, 0xd5, 0x57, 0x8e, 0xbd,
+                0x34, 0xcb, 0x40, 0x99, 0x15, 0x78, 0xf9, 0xf5, 0x2b, 0x18, 0x00, 0x3e, 0xfa, 0x35,
+                0xd3, 0xda, 0x65, 0x53, 0xff, 0x35, 0xdb, 0x91, 0xb8, 0x1a, 0xb8, 0x90, 0xbe, 0xc1,
+                0xb1, 0x89, 0xb7, 0xf5, 0x2c, 0xb2, 0xa7, 0x83, 0xeb, 0xb7, 0xd8, 0x23, 0xd7, 0x25,
+                0xb0, 0xb4, 0xa7, 0x1f, 0x68, 0x24, 0xe8, 0x8f, 0x68, 0xf9, 0x82, 0xee, 0xfc, 0x6d,
+                0x19, 0xc6
+            ]
+        );
+    }
+
     #[test]
     fn verify_sm3() {
         assert_eq!(
@@ -681,4 +1841,34 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn verify_xxhash() {
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Xxh32, &("-", b"Hello, world!")).digest,
+            [0x31, 0xb7, 0x40, 0x5d]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Xxh64, &("-", b"Hello, world!")).digest,
+            [0xf5, 0x83, 0x36, 0xa7, 0x8b, 0x6f, 0x94, 0x76]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Xxh3_64, &("-", b"")).digest,
+            [0x2d, 0x06, 0x80, 0x05, 0x38, 0xd3, 0x94, 0xc2]
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Xxh3_128, &("-", b"")).digest,
+            [
+                0x99, 0xaa, 0x06, 0xd3, 0x01, 0x47, 0x98, 0xd8, 0x60, 0x01, 0xc3, 0x24, 0x46, 0x8d,
+                0x49, 0x7f
+            ]
+        );
+    }
+
+    #[test]
+    fn cryptographic_hash_algorithms() {
+        assert!(HashAlgorithm::Sha256.is_cryptographic());
+        assert!(!HashAlgorithm::Crc32.is_cryptographic());
+        assert!(!HashAlgorithm::Xxh3_64.is_cryptographic());
+    }
 }
@@ -0,0 +1,324 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+#[cfg(feature = "md4")]
+use std::fmt::Write;
+
+#[cfg(feature = "md4")]
+use anyhow::{bail, ensure, Context, Result};
+#[cfg(feature = "md4")]
+use data_encoding::BASE32_NOPAD;
+use digest::{Digest, Output};
+#[cfg(feature = "md4")]
+use md4::Md4;
+#[cfg(feature = "sha-1")]
+use sha1::Sha1;
+
+use crate::digest::Hasher;
+#[cfg(feature = "md4")]
+use crate::value::{Algorithm, Checksum, HashAlgorithm};
+#[cfg(feature = "md4")]
+use crate::{percent, regex};
+
+/// The size of a part (chunk) of the eDonkey2000 network.
+const PART_SIZE: u64 = 9_728_000;
+/// The size of a block of AICH.
+#[cfg(feature = "sha-1")]
+const BLOCK_SIZE: u64 = 184_320;
+/// The number of blocks in a part. The last block of a part is smaller than
+/// the others.
+#[cfg(feature = "sha-1")]
+const BLOCKS_PER_PART: u64 = (PART_SIZE + BLOCK_SIZE - 1) / BLOCK_SIZE;
+
+/// A hasher which computes the ED2K hash.
+///
+/// The input is split into 9,728,000-byte parts, and the MD4 hashes of them
+/// are hashed again with MD4. As eMule does, the hash of an empty last part is
+/// appended if the size of the input is a multiple of the part size, and the
+/// MD4 hash of the input is used as is if the input has only one part.
+#[cfg(feature = "md4")]
+#[derive(Default)]
+pub struct Ed2k {
+    part: Md4,
+    position: u64,
+    hashes: Vec<Output<Md4>>,
+}
+
+#[cfg(feature = "md4")]
+impl Hasher for Ed2k {
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = data_len(PART_SIZE - self.position, data);
+            Digest::update(&mut self.part, &data[..len]);
+            data = &data[len..];
+            self.position += len as u64;
+
+            if self.position == PART_SIZE {
+                self.hashes.push(self.part.finalize_reset());
+                self.position = 0;
+            }
+        }
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        let last = self.part.finalize();
+        if self.hashes.is_empty() {
+            return last.to_vec();
+        }
+
+        let mut hasher = Md4::new();
+        for hash in &self.hashes {
+            Digest::update(&mut hasher, hash);
+        }
+        Digest::update(&mut hasher, last);
+
+        hasher.finalize().to_vec()
+    }
+}
+
+/// A hasher which computes the root hash of the Advanced Intelligent
+/// Corruption Handling (AICH) of eMule.
+///
+/// Each part of the input is split into 184,320-byte blocks, and the SHA-1
+/// hashes of the blocks are combined into a binary tree in the same way as
+/// eMule.
+#[cfg(feature = "sha-1")]
+#[derive(Default)]
+pub struct Aich {
+    block: Sha1,
+    block_position: u64,
+    part_position: u64,
+    size: u64,
+    hashes: Vec<Output<Sha1>>,
+}
+
+#[cfg(feature = "sha-1")]
+impl Aich {
+    /// Compute the hash of the node which covers `size` bytes from `offset`.
+    ///
+    /// A node is split at a part boundary if it is larger than a part, and
+    /// at a block boundary otherwise. If the number of parts or blocks is
+    /// odd, the extra one belongs to the left child of a left branch and the
+    /// right child of a right branch.
+    fn node(&self, offset: u64, size: u64, is_left: bool) -> Output<Sha1> {
+        let base = if size <= PART_SIZE {
+            BLOCK_SIZE
+        } else {
+            PART_SIZE
+        };
+        if size <= base {
+            let index = offset / PART_SIZE * BLOCKS_PER_PART + offset % PART_SIZE / BLOCK_SIZE;
+            return self.hashes[usize::try_from(index).expect("Index exceeds the limit")];
+        }
+
+        let blocks = (size + base - 1) / base;
+        let left_size = if is_left { blocks + 1 } else { blocks } / 2 * base;
+        let left = self.node(offset, left_size, true);
+        let right = self.node(offset + left_size, size - left_size, false);
+
+        Sha1::new()
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+    }
+}
+
+#[cfg(feature = "sha-1")]
+impl Hasher for Aich {
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let remaining = (BLOCK_SIZE - self.block_position).min(PART_SIZE - self.part_position);
+            let len = data_len(remaining, data);
+            Digest::update(&mut self.block, &data[..len]);
+            data = &data[len..];
+            self.block_position += len as u64;
+            self.part_position += len as u64;
+            self.size += len as u64;
+
+            if self.block_position == BLOCK_SIZE || self.part_position == PART_SIZE {
+                self.hashes.push(self.block.finalize_reset());
+                self.block_position = 0;
+            }
+            if self.part_position == PART_SIZE {
+                self.part_position = 0;
+            }
+        }
+    }
+
+    fn finalize(mut self: Box<Self>) -> Vec<u8> {
+        if self.block_position > 0 || self.size == 0 {
+            let hash = self.block.finalize_reset();
+            self.hashes.push(hash);
+        }
+
+        self.node(0, self.size.max(1), true).to_vec()
+    }
+}
+
+#[cfg(feature = "md4")]
+#[cfg(feature = "md4")]
+impl Algorithm {
+    /// Check that the algorithms can be output as eD2k links.
+    ///
+    /// The eD2k links consist of ED2K and optionally AICH.
+    pub fn check_ed2k(algorithms: &[Self]) -> Result<()> {
+        for &algorithm in algorithms {
+            #[cfg(feature = "sha-1")]
+            let is_aich = algorithm == HashAlgorithm::Aich.into();
+            #[cfg(not(feature = "sha-1"))]
+            let is_aich = false;
+            ensure!(
+                algorithm == HashAlgorithm::Ed2k.into() || is_aich,
+                "The ed2k style supports only ED2K and AICH"
+            );
+        }
+        ensure!(
+            algorithms.contains(&HashAlgorithm::Ed2k.into()),
+            "Specify ED2K to output the eD2k links"
+        );
+
+        Ok(())
+    }
+}
+
+impl Checksum {
+    /// Output the eD2k link of a file from its checksums and size.
+    ///
+    /// The checksum of ED2K is required, and the root hash of AICH is added
+    /// to the link if the checksum of it is also given. The name of the file
+    /// is percent-encoded.
+    pub fn ed2k_link(checksums: &[Self], size: u64) -> Result<String> {
+        let mut ed2k = None;
+        #[cfg_attr(not(feature = "sha-1"), allow(unused_mut))]
+        let mut aich: Option<&Self> = None;
+        for checksum in checksums {
+            match checksum.algorithm {
+                Some(a) if a == HashAlgorithm::Ed2k.into() => ed2k = Some(checksum),
+                #[cfg(feature = "sha-1")]
+                Some(a) if a == HashAlgorithm::Aich.into() => aich = Some(checksum),
+                _ => bail!("The ed2k style supports only ED2K and AICH"),
+            }
+        }
+        let ed2k = ed2k.context("Specify ED2K to output the eD2k links")?;
+
+        let name = ed2k
+            .file
+            .file_name()
+            .map_or_else(|| ed2k.file.to_string_lossy(), |n| n.to_string_lossy());
+        let mut link = format!(
+            "ed2k://|file|{}|{}|{}|",
+            percent::encode(&name),
+            size,
+            hex::encode_upper(&ed2k.digest)
+        );
+        if let Some(aich) = aich {
+            write!(link, "h={}|", BASE32_NOPAD.encode(&aich.digest))
+                .expect("Failed to write to a string");
+        }
+        link.push('/');
+
+        Ok(link)
+    }
+
+    /// Parse an eD2k link as a checksum of ED2K.
+    ///
+    /// The size in the link is also verified. The other hashes in the link,
+    /// such as the root hash of AICH, are ignored.
+    pub fn parse_ed2k_link(link: &str) -> Option<Self> {
+        let captures = regex!(
+            r"(?i)^ed2k://\|file\|(?P<file>[^|]+)\|(?P<size>[[:digit:]]+)\|(?P<digest>[[:xdigit:]]{32})\|(?:[^|]*\|)*/$"
+        )
+        .captures(link)?;
+
+        Some(Self {
+            algorithm: Some(HashAlgorithm::Ed2k.into()),
+            file: percent::decode(&captures["file"])?.into(),
+            digest: hex::decode(&captures["digest"]).ok()?,
+            size: Some(captures["size"].parse().ok()?),
+            alternatives: Vec::new(),
+        })
+    }
+}
+
+/// Get the number of bytes to take from the data without exceeding
+/// `remaining`.
+fn data_len(remaining: u64, data: &[u8]) -> usize {
+    usize::try_from(remaining).map_or(data.len(), |r| r.min(data.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "md4")]
+    fn ed2k(data: &[u8]) -> String {
+        let mut hasher = Box::new(Ed2k::default());
+        Hasher::update(hasher.as_mut(), data);
+
+        hex::encode(hasher.finalize())
+    }
+
+    #[cfg(feature = "sha-1")]
+    fn aich(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Box::new(Aich::default());
+        for chunk in data.chunks(100_000) {
+            Hasher::update(hasher.as_mut(), chunk);
+        }
+
+        hasher.finalize()
+    }
+
+    #[cfg(feature = "md4")]
+    #[test]
+    fn verify_ed2k() {
+        assert_eq!(ed2k(b""), "31d6cfe0d16ae931b73c59d7e0c089c0");
+        assert_eq!(ed2k(b"abc"), "a448017aaf21d8525fc10ae87aa6729d");
+        assert_eq!(
+            ed2k(&vec![u8::default(); 9_728_000]),
+            "fc21d9af828f92a8df64beac3357425d"
+        );
+    }
+
+    #[cfg(feature = "md4")]
+    #[test]
+    fn ed2k_link() {
+        let checksums = [Checksum::digest(
+            HashAlgorithm::Ed2k,
+            &("foo/Hello, world!.txt", "abc"),
+        )];
+        let link = Checksum::ed2k_link(&checksums, 3).unwrap();
+        assert_eq!(
+            link,
+            "ed2k://|file|Hello%2C%20world%21.txt|3|A448017AAF21D8525FC10AE87AA6729D|/"
+        );
+
+        let checksum = Checksum::parse_ed2k_link(&link).unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Ed2k.into()));
+        assert_eq!(checksum.file.to_str(), Some("Hello, world!.txt"));
+        assert_eq!(checksum.digest, checksums[0].digest);
+        assert_eq!(checksum.size, Some(3));
+
+        assert!(Checksum::parse_ed2k_link(
+            "ed2k://|file|foo|3|a448017aaf21d8525fc10ae87aa6729d|h=ABC|/"
+        )
+        .is_some());
+        assert!(Checksum::parse_ed2k_link("ed2k://|file|foo|3|a448017a|/").is_none());
+    }
+
+    #[cfg(feature = "sha-1")]
+    #[test]
+    fn verify_aich() {
+        assert_eq!(aich(b"abc"), Sha1::digest(b"abc").as_slice());
+        assert_eq!(
+            hex::encode(aich(&[0x61; 5_000_000])),
+            "216e63ccfc27cf1a8d82c48acde93394c99f2663"
+        );
+        assert_eq!(
+            hex::encode(aich(&[0x61; 20_000_000])),
+            "77e14d0b9c45a70d1f4a8199cba9427de124ed4f"
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use data_encoding::{BASE32_NOPAD, BASE64, BASE64URL_NOPAD, BASE64_NOPAD};
+
+use crate::value::{Encoding, Multibase};
+
+/// The lengths of the message digests of AICH and TTH in bytes, which are
+/// detected in base32.
+const BASE32_LENGTHS: [usize; 2] = [20, 24];
+
+/// The alphabet of the base32 used by Nix, which omits `e`, `o`, `t` and `u`.
+const NIX32_ALPHABET: &[u8; 32] = b"0123456789abcdfghijklmnpqrsvwxyz";
+
+impl Encoding {
+    /// Encode the data into a string.
+    pub fn encode(self, data: &[u8]) -> String {
+        match self {
+            Self::Hex => hex::encode(data),
+            Self::UpperHex => hex::encode_upper(data),
+            Self::Base32 => BASE32_NOPAD.encode(data),
+            Self::Nix32 => nix32_encode(data),
+            Self::Base64 => BASE64.encode(data),
+            Self::Base64url => BASE64URL_NOPAD.encode(data),
+            Self::Base58 => bs58::encode(data).into_string(),
+        }
+    }
+
+    /// Decode the string into raw bytes.
+    ///
+    /// Both cases are accepted for hexadecimal and base32, and the padding is
+    /// optional for base64.
+    pub fn decode(self, str: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Hex | Self::UpperHex => hex::decode(str).ok(),
+            Self::Base32 => BASE32_NOPAD
+                .decode(str.to_ascii_uppercase().as_bytes())
+                .ok(),
+            Self::Nix32 => nix32_decode(str),
+            Self::Base64 => BASE64
+                .decode(str.as_bytes())
+                .or_else(|_| BASE64_NOPAD.decode(str.as_bytes()))
+                .ok(),
+            Self::Base64url => BASE64URL_NOPAD
+                .decode(str.trim_end_matches('=').as_bytes())
+                .ok(),
+            Self::Base58 => bs58::decode(str).into_vec().ok(),
+        }
+    }
+}
+
+/// Decode the message digest in any of the encodings.
+///
+/// The encodings are tried in the order of hexadecimal, the multibase, base32,
+/// the base32 of Nix, base64 and base58. Returns the distinct decodings in
+/// this order, since a string can be valid in multiple encodings. If the
+/// length of the message digest is known, only the decodings which result in
+/// the length are returned. Base32 is detected only for the lengths of TTH and
+/// AICH, which are usually encoded in it.
+pub fn decode_any(str: &str, len: Option<usize>) -> Vec<Vec<u8>> {
+    let others = [
+        Encoding::Nix32,
+        Encoding::Base64,
+        Encoding::Base64url,
+        Encoding::Base58,
+    ];
+    let base32 = Encoding::Base32
+        .decode(str)
+        .filter(|d| BASE32_LENGTHS.contains(&d.len()));
+    let candidates = Encoding::Hex
+        .decode(str)
+        .into_iter()
+        .chain(Multibase::decode(str))
+        .chain(base32)
+        .chain(others.iter().filter_map(|e| e.decode(str)))
+        .filter(|d| len.map_or(true, |len| d.len() == len));
+
+    let mut digests: Vec<Vec<u8>> = Vec::new();
+    for digest in candidates {
+        if !digests.contains(&digest) {
+            digests.push(digest);
+        }
+    }
+
+    digests
+}
+
+/// Encode the data in the base32 of Nix.
+///
+/// Unlike RFC 4648, the data is encoded from the last byte, and the bits of
+/// each byte are read from the least significant bit.
+fn nix32_encode(data: &[u8]) -> String {
+    let len = (data.len() * 8 + 4) / 5;
+    (0..len)
+        .rev()
+        .map(|n| {
+            let (i, j) = (n * 5 / 8, n * 5 % 8);
+            let word = u16::from(data[i]) | data.get(i + 1).map_or(0, |&b| u16::from(b) << 8);
+            char::from(NIX32_ALPHABET[usize::from(word >> j & 0x1f)])
+        })
+        .collect()
+}
+
+/// Decode the string in the base32 of Nix.
+///
+/// Returns `None` if the string has an invalid character or the extra bits
+/// are not zero.
+fn nix32_decode(str: &str) -> Option<Vec<u8>> {
+    let len = str.len() * 5 / 8;
+    let mut data = vec![u8::default(); len];
+    for (n, c) in str.bytes().rev().enumerate() {
+        let digit = NIX32_ALPHABET.iter().position(|&a| a == c)? as u16;
+        let (i, j) = (n * 5 / 8, n * 5 % 8);
+        let value = digit << j;
+        match data.get_mut(i) {
+            Some(byte) => *byte |= value as u8,
+            None if value == 0 => (),
+            None => return None,
+        }
+        let carry = (value >> 8) as u8;
+        match data.get_mut(i + 1) {
+            Some(byte) => *byte |= carry,
+            None if carry == 0 => (),
+            None => return None,
+        }
+    }
+
+    Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ArgEnum;
+
+    use super::*;
+
+    #[test]
+    fn encode_digest() {
+        let digest =
+            hex::decode("315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3")
+                .unwrap();
+
+        assert_eq!(
+            Encoding::Base64.encode(&digest),
+            "MV9b23bQeMQ7isAGTkoBZGErH853yGk0W/yUx1iU7dM="
+        );
+        assert_eq!(
+            Encoding::Base64url.encode(&digest),
+            "MV9b23bQeMQ7isAGTkoBZGErH853yGk0W_yUx1iU7dM"
+        );
+        assert_eq!(
+            Encoding::Nix32.encode(&digest),
+            "1lzdjiccg57wbcs6kj3prqgjnqb40554w1n0i8xw8y6hfvdmnpri"
+        );
+        assert_eq!(
+            Encoding::Base58.encode(&digest),
+            "4KjK9yP5KTEkyBznKmB5MVLhgFgRedBprbx4nYMCZQYa"
+        );
+
+        for encoding in Encoding::value_variants() {
+            assert_eq!(
+                encoding.decode(&encoding.encode(&digest)),
+                Some(digest.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn decode_any_encoding() {
+        let digest = vec![0xeb, 0xe6, 0xc6, 0xe6];
+
+        assert_eq!(decode_any("ebe6c6e6", None)[0], digest);
+        assert_eq!(decode_any("6+bG5g==", None), [digest.as_slice()]);
+        assert_eq!(decode_any("72jc85", Some(4)), [digest.as_slice()]);
+        assert_eq!(decode_any("z72jc85", None)[0], digest);
+        assert!(decode_any("!", None).is_empty());
+        assert!(nix32_decode("zz").is_none());
+
+        let tth = [0xeb; 24];
+        assert_eq!(
+            decode_any(&BASE32_NOPAD.encode(&tth), Some(24)),
+            [tth.to_vec()]
+        );
+        let sha256 = [0xeb; 32];
+        assert!(decode_any(&BASE32_NOPAD.encode(&sha256), Some(32)).is_empty());
+
+        // The string is valid in both base32 and the base32 of Nix.
+        let sha1 = [0x17; 20];
+        let nix32 = Encoding::Nix32.encode(&sha1);
+        let digests = decode_any(&nix32, Some(20));
+        assert_eq!(digests.len(), 2);
+        assert!(digests.contains(&sha1.to_vec()));
+    }
+}
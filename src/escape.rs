@@ -0,0 +1,70 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+/// Escape the file name in the same way as GNU coreutils.
+///
+/// The backslash, the line feed and the carriage return are escaped. Returns
+/// `None` if the file name has none of them, since the checksum line of such
+/// a file name is not prefixed with a backslash.
+pub fn escape(file: &[u8]) -> Option<Vec<u8>> {
+    if !file.iter().any(|b| matches!(b, b'\\' | b'\n' | b'\r')) {
+        return None;
+    }
+
+    let escaped = file
+        .iter()
+        .fold(Vec::with_capacity(file.len()), |mut escaped, &b| {
+            match b {
+                b'\\' => escaped.extend_from_slice(b"\\\\"),
+                b'\n' => escaped.extend_from_slice(b"\\n"),
+                b'\r' => escaped.extend_from_slice(b"\\r"),
+                b => escaped.push(b),
+            }
+            escaped
+        });
+
+    Some(escaped)
+}
+
+/// Unescape the file name escaped by GNU coreutils.
+///
+/// Returns `None` if the file name has an invalid escape sequence.
+pub fn unescape(file: &[u8]) -> Option<Vec<u8>> {
+    let mut unescaped = Vec::with_capacity(file.len());
+    let mut bytes = file.iter();
+    while let Some(&b) = bytes.next() {
+        if b != b'\\' {
+            unescaped.push(b);
+            continue;
+        }
+
+        match bytes.next()? {
+            b'\\' => unescaped.push(b'\\'),
+            b'n' => unescaped.push(b'\n'),
+            b'r' => unescaped.push(b'\r'),
+            _ => return None,
+        }
+    }
+
+    Some(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_file_name() {
+        assert_eq!(escape(b"foo.txt"), None);
+        assert_eq!(escape(b"a\\b\nc\rd").unwrap(), br"a\\b\nc\rd");
+
+        assert_eq!(unescape(br"a\\b\nc\rd").unwrap(), b"a\\b\nc\rd");
+        assert_eq!(unescape(b"foo.txt").unwrap(), b"foo.txt");
+        assert_eq!(unescape(b"caf\xe9.txt").unwrap(), b"caf\xe9.txt");
+        assert!(unescape(br"a\tb").is_none());
+        assert!(unescape(b"a\\").is_none());
+    }
+}
@@ -0,0 +1,358 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::fs::{self, File, Metadata};
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use anyhow::{ensure, Context, Result};
+
+use crate::value::{Algorithm, Checksum, Params};
+use crate::walk::{self, Filter};
+
+/// The mode of a regular file in a tree object.
+const MODE_FILE: &[u8] = b"100644";
+/// The mode of an executable file in a tree object.
+const MODE_EXECUTABLE: &[u8] = b"100755";
+/// The mode of a symbolic link in a tree object.
+const MODE_SYMLINK: &[u8] = b"120000";
+/// The mode of a subdirectory in a tree object.
+const MODE_TREE: &[u8] = b"40000";
+
+/// An entry of a tree object.
+struct Entry {
+    mode: &'static [u8],
+    name: Vec<u8>,
+    /// The object IDs of the entry for each algorithm.
+    ids: Vec<Vec<u8>>,
+}
+
+impl Entry {
+    /// Get the key for sorting the entries in the order used by git, which
+    /// compares the name of a subdirectory as if it ends with `/`.
+    fn sort_key(&self) -> Vec<u8> {
+        let mut key = self.name.clone();
+        if self.mode == MODE_TREE {
+            key.push(b'/');
+        }
+
+        key
+    }
+}
+
+impl Checksum {
+    /// Compute the git blob IDs of the data read from the reader.
+    ///
+    /// The blob object is the data prefixed with the header `blob <size>\0`,
+    /// so the size of the data must be known in advance. Returns an error if
+    /// the number of bytes read differs from `size`.
+    pub fn git_blob(
+        algorithms: &[Algorithm],
+        file: impl AsRef<Path>,
+        size: u64,
+        reader: impl Read,
+    ) -> Result<(Vec<Self>, u64)> {
+        let (checksums, length) = object(algorithms, "blob", file.as_ref(), size, reader)?;
+        ensure!(
+            length == size,
+            "{} was changed while reading",
+            file.as_ref().display()
+        );
+
+        Ok((checksums, length))
+    }
+
+    /// Compute the git blob IDs of stdin.
+    ///
+    /// If stdin is redirected from a regular file, the data is streamed with
+    /// the size of the file. Otherwise, the data is read into memory first,
+    /// since the size is needed for the header.
+    pub fn git_stdin(algorithms: &[Algorithm], mut reader: impl Read) -> Result<(Vec<Self>, u64)> {
+        let file = Path::new("-");
+        if let Some(size) = stdin_size() {
+            return Self::git_blob(algorithms, file, size, reader);
+        }
+
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .context("Failed to read bytes from stdin")?;
+        let size = u64::try_from(data.len()).expect("Input size exceeds the limit");
+
+        Self::git_blob(algorithms, file, size, data.as_slice())
+    }
+
+    /// Compute the git object IDs of the file or directory.
+    ///
+    /// A directory is hashed as a tree object in the same way as
+    /// `git write-tree`, and anything else is hashed as a blob object in the
+    /// same way as `git hash-object`. The files and directories not selected
+    /// by the filter, `.git` directories and empty directories are not
+    /// included in a tree object. Returns the checksums and the total size of
+    /// the files.
+    pub fn git_object(
+        algorithms: &[Algorithm],
+        path: impl AsRef<Path>,
+        filter: &Filter,
+    ) -> Result<(Vec<Self>, u64)> {
+        let path = path.as_ref();
+
+        if !path.is_dir() {
+            let file =
+                File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+            let size = file
+                .metadata()
+                .with_context(|| format!("Failed to get the metadata of {}", path.display()))?
+                .len();
+
+            return Self::git_blob(algorithms, path, size, file);
+        }
+
+        let mut filter = filter.clone();
+        let (entries, length) = tree_entries(algorithms, path, &mut filter)?;
+        let checksums = algorithms
+            .iter()
+            .zip(tree_ids(algorithms, path, entries)?)
+            .map(|(&algorithm, digest)| Self {
+                algorithm: Some(algorithm),
+                file: path.to_path_buf(),
+                digest,
+                size: None,
+                alternatives: Vec::new(),
+            })
+            .collect();
+
+        Ok((checksums, length))
+    }
+}
+
+/// Get the size of stdin if it is redirected from a regular file.
+#[cfg(unix)]
+fn stdin_size() -> Option<u64> {
+    fs::metadata("/dev/stdin")
+        .ok()
+        .filter(Metadata::is_file)
+        .map(|m| m.len())
+}
+
+/// Get the size of stdin if it is redirected from a regular file.
+#[cfg(not(unix))]
+const fn stdin_size() -> Option<u64> {
+    None
+}
+
+/// Compute the IDs of the object of the kind.
+fn object(
+    algorithms: &[Algorithm],
+    kind: &str,
+    file: &Path,
+    size: u64,
+    reader: impl Read,
+) -> Result<(Vec<Checksum>, u64)> {
+    let header = format!("{} {}\0", kind, size);
+    let (checksums, length) = Checksum::digest_reader(
+        algorithms,
+        &Params::default(),
+        file,
+        Cursor::new(header.as_bytes()).chain(reader),
+    )?;
+    let header_length = u64::try_from(header.len()).expect("Header length exceeds the limit");
+
+    Ok((checksums, length - header_length))
+}
+
+/// Compute the IDs of the tree object which consists of the entries for each
+/// algorithm.
+///
+/// The tree object differs for each algorithm, since it contains the IDs of
+/// the entries.
+fn tree_ids(algorithms: &[Algorithm], dir: &Path, mut entries: Vec<Entry>) -> Result<Vec<Vec<u8>>> {
+    entries.sort_by_cached_key(Entry::sort_key);
+
+    algorithms
+        .iter()
+        .enumerate()
+        .map(|(i, &algorithm)| {
+            let mut tree = Vec::new();
+            for entry in &entries {
+                tree.extend_from_slice(entry.mode);
+                tree.push(b' ');
+                tree.extend_from_slice(&entry.name);
+                tree.push(b'\0');
+                tree.extend_from_slice(&entry.ids[i]);
+            }
+            let size = u64::try_from(tree.len()).expect("Tree size exceeds the limit");
+            let (mut checksums, _) = object(&[algorithm], "tree", dir, size, tree.as_slice())?;
+
+            Ok(checksums.remove(0).digest)
+        })
+        .collect()
+}
+
+/// Collect the entries of the tree object of the directory.
+///
+/// Each file is read only once for all the algorithms. Returns the entries
+/// and the total size of the files.
+fn tree_entries(
+    algorithms: &[Algorithm],
+    dir: &Path,
+    filter: &mut Filter,
+) -> Result<(Vec<Entry>, u64)> {
+    let mut entries = Vec::new();
+    let mut length = u64::default();
+    let ids = |checksums: Vec<Checksum>| checksums.into_iter().map(|c| c.digest).collect();
+
+    let read_dir = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read the directory {}", dir.display()))?;
+    for entry in read_dir {
+        let entry =
+            entry.with_context(|| format!("Failed to read the directory {}", dir.display()))?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let file_type = entry
+            .file_type()
+            .with_context(|| format!("Failed to get the file type of {}", path.display()))?;
+
+        if name == ".git" {
+            continue;
+        }
+
+        let (mode, ids) = if file_type.is_dir() {
+            if filter.is_ignored(&path, true) {
+                continue;
+            }
+
+            let (subentries, size) = tree_entries(algorithms, &path, filter)?;
+            if subentries.is_empty() {
+                continue;
+            }
+            length += size;
+
+            (MODE_TREE, tree_ids(algorithms, &path, subentries)?)
+        } else if file_type.is_file() {
+            if !filter.is_match(&path) {
+                continue;
+            }
+
+            let file =
+                File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+            let metadata = file
+                .metadata()
+                .with_context(|| format!("Failed to get the metadata of {}", path.display()))?;
+            let (checksums, size) = Checksum::git_blob(algorithms, &path, metadata.len(), file)?;
+            length += size;
+
+            let mode = if walk::is_executable(&metadata) {
+                MODE_EXECUTABLE
+            } else {
+                MODE_FILE
+            };
+            (mode, ids(checksums))
+        } else if file_type.is_symlink() {
+            if !filter.is_match(&path) {
+                continue;
+            }
+
+            // The blob of a symbolic link is the path of its target.
+            let target = fs::read_link(&path)
+                .with_context(|| format!("Failed to read the symbolic link {}", path.display()))?;
+            let target = walk::to_bytes(target.as_os_str())?;
+            let size = u64::try_from(target.len()).expect("Path length exceeds the limit");
+            let (checksums, _) = Checksum::git_blob(algorithms, &path, size, target.as_slice())?;
+
+            (MODE_SYMLINK, ids(checksums))
+        } else {
+            continue;
+        };
+
+        entries.push(Entry {
+            mode,
+            name: walk::to_bytes(&name)?,
+            ids,
+        });
+    }
+
+    Ok((entries, length))
+}
+
+#[cfg(all(test, feature = "sha-1"))]
+mod tests {
+    use super::*;
+    use crate::value::HashAlgorithm;
+
+    #[test]
+    fn git_blob() {
+        let data = b"Hello, world!";
+        let algorithms = [
+            Algorithm::git(HashAlgorithm::Sha1).unwrap(),
+            Algorithm::git(HashAlgorithm::Sha256).unwrap(),
+        ];
+        let (checksums, length) =
+            Checksum::git_blob(&algorithms, "-", 13, data.as_slice()).unwrap();
+
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "5dd01c177f5d7d1be5346a5bc18a569a7410c2ef"
+        );
+        assert_eq!(
+            hex::encode(&checksums[1].digest),
+            "178b5fbed164aee269fee7323badf7269cca0eed0875717b0d2d4f9819164c3f"
+        );
+        assert_eq!(length, 13);
+
+        assert!(Checksum::git_blob(&algorithms, "-", 12, data.as_slice()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn git_tree() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path();
+        fs::write(path.join("foo"), "foo\n").unwrap();
+        fs::set_permissions(path.join("foo"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(path.join("bar.txt"), "bar\n").unwrap();
+        fs::create_dir(path.join("bar")).unwrap();
+        fs::write(path.join("bar/baz.txt"), "baz\n").unwrap();
+        fs::create_dir(path.join("empty")).unwrap();
+        symlink("foo", path.join("link")).unwrap();
+
+        let algorithms = [
+            Algorithm::git(HashAlgorithm::Sha1).unwrap(),
+            Algorithm::git(HashAlgorithm::Sha256).unwrap(),
+        ];
+        let filter = Filter::new(&[], &[], false).unwrap();
+        let (checksums, length) = Checksum::git_object(&algorithms, path, &filter).unwrap();
+
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "9505cd3cb31891d04a906ab9ba46e9270f24db35"
+        );
+        assert_eq!(
+            hex::encode(&checksums[1].digest),
+            "d993cb1b1894d28b2804bd84670364a8eb1eb9dfb627ef14ffa380792169cf3d"
+        );
+        assert_eq!(length, 12);
+    }
+
+    #[test]
+    fn empty_git_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let filter = Filter::new(&[], &[], false).unwrap();
+        let (checksums, _) = Checksum::git_object(
+            &[Algorithm::git(HashAlgorithm::Sha1).unwrap()],
+            dir.path(),
+            &filter,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "4b825dc642cb6eb9a060e54bf8d69288fbee4904"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use anyhow::{anyhow, ensure, Result};
+
+use crate::value::{Algorithm, Checksum, ImproperLine};
+
+/// The first line of the files in the format of hashdeep.
+const HASHDEEP_HEADER: &str = "%%%% HASHDEEP-1.0";
+/// The prefix of the header lines.
+const HEADER_PREFIX: &str = "%%%% ";
+/// The prefix of the comment lines.
+const COMMENT_PREFIX: &str = "##";
+
+impl Checksum {
+    /// Output the checksums of the files in the format of hashdeep.
+    ///
+    /// Each line consists of the size of the file, the message digests in
+    /// hexadecimal and the file name, which are separated by commas. The
+    /// columns are named after the hash algorithms in lowercase, so hashdeep
+    /// itself can read only `md5`, `sha1`, `sha256`, `tiger` and `whirlpool`.
+    pub fn hashdeep(algorithms: &[Algorithm], checksums: &[Vec<Self>], lengths: &[u64]) -> Vec<u8> {
+        let columns: Vec<_> = algorithms
+            .iter()
+            .map(|a| a.to_string().to_ascii_lowercase())
+            .collect();
+        let mut output = format!(
+            "{}\n{}size,{},filename\n",
+            HASHDEEP_HEADER,
+            HEADER_PREFIX,
+            columns.join(",")
+        )
+        .into_bytes();
+        for (checksums, length) in checksums.iter().zip(lengths) {
+            let digests: Vec<_> = checksums.iter().map(|c| hex::encode(&c.digest)).collect();
+            output.extend(format!("{},{},", length, digests.join(",")).into_bytes());
+            output.extend(checksums[0].file_bytes());
+            output.push(b'\n');
+        }
+
+        output
+    }
+
+    /// Parse the checksums in the format of hashdeep.
+    ///
+    /// Returns `None` if the text does not start with the header of hashdeep.
+    /// Otherwise, returns the checksums and the improperly formatted lines
+    /// with the errors. The columns of unknown hash algorithms are ignored.
+    pub fn from_hashdeep(str: &str) -> Option<(Vec<Self>, Vec<ImproperLine>)> {
+        let mut lines = str.lines().enumerate();
+        if lines.next()?.1 != HASHDEEP_HEADER {
+            return None;
+        }
+
+        let (mut checksums, mut impropers) = (Vec::new(), Vec::new());
+        let mut columns = None;
+        for (i, line) in lines {
+            if line.is_empty() || line.starts_with(COMMENT_PREFIX) || line == HASHDEEP_HEADER {
+                continue;
+            }
+            // The header can appear again if the files are concatenated.
+            if let Some(header) = line.strip_prefix(HEADER_PREFIX) {
+                match parse_columns(header) {
+                    Ok(c) => columns = Some(c),
+                    Err(error) => {
+                        columns = None;
+                        impropers.push((i, error));
+                    }
+                }
+
+                continue;
+            }
+
+            match columns
+                .as_deref()
+                .ok_or_else(|| anyhow!("Improperly formatted checksum line"))
+                .and_then(|c| parse_line(line, c))
+            {
+                Ok(c) => checksums.extend(c),
+                Err(error) => impropers.push((i, error)),
+            }
+        }
+
+        Some((checksums, impropers))
+    }
+}
+
+/// Parse the header line which specifies the columns of the checksum lines.
+///
+/// Returns the algorithms of the columns of the message digests.
+fn parse_columns(header: &str) -> Result<Vec<Option<Algorithm>>> {
+    let columns: Vec<_> = header.split(',').collect();
+    ensure!(
+        columns.len() >= 2 && columns[0] == "size" && columns[columns.len() - 1] == "filename",
+        "Improperly formatted header line"
+    );
+
+    Ok(columns[1..columns.len() - 1]
+        .iter()
+        .map(|c| c.parse().ok())
+        .collect())
+}
+
+/// Parse a checksum line of hashdeep.
+///
+/// The file name is the last column, so it can contain commas.
+fn parse_line(line: &str, columns: &[Option<Algorithm>]) -> Result<Vec<Checksum>> {
+    let fields: Vec<_> = line.splitn(columns.len() + 2, ',').collect();
+    ensure!(
+        fields.len() == columns.len() + 2,
+        "Improperly formatted checksum line"
+    );
+    let size = fields[0]
+        .parse()
+        .map_err(|_| anyhow!("Improperly formatted checksum line"))?;
+    let file = fields[fields.len() - 1];
+
+    columns
+        .iter()
+        .zip(&fields[1..])
+        .filter_map(|(algorithm, digest)| algorithm.map(|a| (a, digest)))
+        .map(|(algorithm, digest)| {
+            let digest = hex::decode(digest)
+                .ok()
+                .filter(|d| d.len() == algorithm.digest_len())
+                .ok_or_else(|| anyhow!("Improperly formatted checksum line"))?;
+
+            Ok(Checksum {
+                algorithm: Some(algorithm),
+                file: file.into(),
+                digest,
+                size: Some(size),
+                alternatives: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::value::HashAlgorithm;
+
+    #[test]
+    fn hashdeep() {
+        let algorithms = [HashAlgorithm::Sha256.into(), HashAlgorithm::Tiger.into()];
+        let checksums = vec![
+            Checksum::digest(HashAlgorithm::Sha256, &("a,b.txt", b"Hello, world!")),
+            Checksum::digest(HashAlgorithm::Tiger, &("a,b.txt", b"Hello, world!")),
+        ];
+        let output = Checksum::hashdeep(&algorithms, &[checksums], &[13]);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "%%%% HASHDEEP-1.0\n\
+             %%%% size,sha256,tiger,filename\n\
+             13,315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3,\
+             b5e5dd73a5894236937084131bb845189cdc5477579b9f36,a,b.txt\n"
+        );
+
+        let (checksums, impropers) = Checksum::from_hashdeep(&format!(
+            "{}## Invoked from: /tmp\n\
+             13,315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3,\
+             b5e5dd73a5894236937084131bb845189cdc5477579b9f36\n",
+            output
+        ))
+        .unwrap();
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums[0].file, Path::new("a,b.txt"));
+        assert_eq!(checksums[1].algorithm, Some(HashAlgorithm::Tiger.into()));
+        assert_eq!(impropers.len(), 1);
+        assert_eq!(impropers[0].0, 4);
+
+        assert!(Checksum::from_hashdeep("13,abcd,foo.txt").is_none());
+    }
+    #[test]
+    #[cfg(unix)]
+    fn hashdeep_non_utf8_file_name() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let file = Path::new(OsStr::from_bytes(b"\xff.txt"));
+        let checksums = vec![Checksum::digest(HashAlgorithm::Sha256, &(file, b""))];
+        let output = Checksum::hashdeep(&[HashAlgorithm::Sha256.into()], &[checksums], &[0]);
+        assert!(output.ends_with(b",\xff.txt\n"));
+    }
+}
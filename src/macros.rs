@@ -15,6 +15,18 @@ macro_rules! regex {
     }};
 }
 
+#[macro_export]
+macro_rules! bytes_regex {
+    ($regex:literal $(,)?) => {{
+        static REGEX: once_cell::sync::OnceCell<regex::bytes::Regex> =
+            once_cell::sync::OnceCell::new();
+
+        REGEX.get_or_init(|| {
+            regex::bytes::Regex::new($regex).expect("Failed to compile a regular expression")
+        })
+    }};
+}
+
 #[macro_export]
 macro_rules! long_version {
     () => {{
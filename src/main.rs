@@ -4,37 +4,63 @@
 // Copyright (C) 2021 Shun Sakai
 //
 
+mod audit;
+mod bencode;
 mod cli;
 mod config;
 mod digest;
+#[cfg(any(feature = "md4", feature = "sha-1"))]
+mod ed2k;
+mod encoding;
+mod escape;
+mod git;
+mod hashdeep;
 mod macros;
+mod multihash;
 mod output;
+mod percent;
+mod sri;
+mod template;
+mod torrent;
+mod tree;
+mod tth;
 mod value;
 mod verify;
+mod walk;
 
 use std::collections::BTreeMap;
-use std::fs;
-use std::io::{self, Read};
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 use std::str;
 use std::time::Instant;
 
 use anyhow::{ensure, Context, Result};
-use clap::Parser;
+use clap::{ArgEnum, Parser};
 use dialoguer::theme::ColorfulTheme;
 use indicatif::{BinaryBytes, ParallelProgressIterator, ProgressBar, ProgressStyle};
-use maplit::btreemap;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use strum::VariantNames;
 
+use crate::audit::{Audit, AuditStatus};
 use crate::cli::Opt;
-use crate::value::{Checksum, HashAlgorithm, Style};
+use crate::template::Template;
+use crate::torrent::{Torrent, DEFAULT_PIECE_LENGTH};
+use crate::tth::{TreeReader, Tth, TthTree};
+use crate::value::{
+    Algorithm, Checksum, DigestFormat, Encoding, HashAlgorithm, ImproperLine, MultiChecksum,
+    Multiformat, Params, Style,
+};
 use crate::verify::{Verify, VERIFICATION_RESULT_WIDTH};
+use crate::walk::Filter;
 
 const PROGRESS_BAR_TEMPLATE: &str =
     "{spinner:.green} [{elapsed_precise}] {percent}% {wide_bar:.cyan/blue} {pos}/{len} ETA {eta}";
 
 fn main() -> Result<()> {
-    let opt = Opt::parse().apply_config()?;
+    let mut opt = Opt::parse().apply_config()?;
+    if opt.tag {
+        opt.style = Style::Bsd;
+    }
 
     rayon::ThreadPoolBuilder::new()
         .num_threads(opt.threads)
@@ -52,111 +78,161 @@ fn main() -> Result<()> {
     }
 
     if opt.list_hash_algorithms {
-        for hash_algorithm in HashAlgorithm::VARIANTS {
-            println!("{}", hash_algorithm);
+        for hash_algorithm in HashAlgorithm::value_variants() {
+            if hash_algorithm.is_cryptographic() {
+                println!("{}", hash_algorithm);
+            } else {
+                println!("{} (non-cryptographic)", hash_algorithm);
+            }
         }
 
         return Ok(());
     }
 
-    let (files, dirs): (Vec<_>, Vec<_>) = opt.input.iter().cloned().partition(|i| i.is_file());
+    let mut filter = Filter::new(&opt.include, &opt.exclude, opt.gitignore)?;
 
-    let inputs = if files.is_empty() {
+    let (files, dirs): (Vec<_>, Vec<_>) = opt.input.iter().cloned().partition(|i| i.is_file());
+    let mut files: Vec<_> = if opt.check {
+        files
+    } else {
+        files.into_iter().filter(|f| filter.is_match(f)).collect()
+    };
+    let (walked, dirs): (Vec<_>, Vec<_>) =
+        dirs.into_iter().partition(|d| opt.recursive && d.is_dir());
+    let is_walked = !walked.is_empty();
+    for dir in walked {
+        files.extend(walk::walk(dir, &mut filter)?);
+    }
+    // A directory is hashed as a whole when computing the git object IDs, the
+    // tree digests or the info-hashes.
+    let (trees, dirs): (Vec<_>, Vec<_>) = dirs
+        .into_iter()
+        .partition(|d| (opt.git || opt.tree || opt.torrent) && !opt.check && d.is_dir());
+    files.extend(trees);
+    files.sort();
+    files.dedup();
+
+    let inputs = if files.is_empty() && !is_walked {
         ensure!(
-            opt.hash_algorithm.is_some(),
+            !opt.hash_algorithm.is_empty() || opt.format.is_some() || opt.audit.is_some(),
             "Unable to determine hash algorithm"
         );
 
-        let input = if atty::is(atty::Stream::Stdin) {
-            dialoguer::Input::<String>::with_theme(&ColorfulTheme::default())
-                .with_prompt("Input")
-                .interact()
-                .context("Failed to read a string from stdin")?
-                .into_bytes()
-        } else {
-            let mut buf = Vec::new();
-            io::stdin()
-                .read_to_end(&mut buf)
-                .context("Failed to read bytes from stdin")?;
-            buf
-        };
-
-        btreemap!("-".into() => input)
+        vec![PathBuf::from("-")]
     } else {
-        let data: Result<Vec<_>> = files
-            .iter()
+        files
+    };
+
+    let params = Params {
+        key: opt
+            .key_file
+            .as_ref()
             .map(|f| {
-                fs::read(f).with_context(|| format!("Failed to read bytes from {}", f.display()))
+                fs::read(f).with_context(|| format!("Failed to read the key from {}", f.display()))
             })
-            .collect();
-
-        files.into_iter().zip(data?.into_iter()).collect()
+            .transpose()?,
+        customization: opt.customization.as_ref().map(|s| s.as_bytes().to_vec()),
+        piece_length: opt.piece_length,
     };
 
     let start = Instant::now();
     let mut total_length = u64::default();
+    let mut is_audit_failed = bool::default();
 
     if opt.check {
+        ensure!(
+            opt.hash_algorithm.len() <= 1,
+            "Only one hash algorithm can be specified when verifying"
+        );
+
+        let algorithm = opt
+            .hash_algorithm
+            .first()
+            .map(|&hash| new_algorithm(&opt, hash))
+            .transpose()?;
+
         let mut results = BTreeMap::new();
         let mut is_improper = bool::default();
 
-        for (i, (path, data)) in inputs.iter().enumerate() {
+        for (i, path) in inputs.iter().enumerate() {
             let start = Instant::now();
 
-            let str = str::from_utf8(data).context("Failed to convert from bytes to a string")?;
-
-            let mut impropers = Vec::new();
-            let checksums: Vec<Checksum> = if let Ok(checksums) = serde_json::from_str(str) {
-                checksums
-            } else {
-                for (i, line) in str.lines().enumerate() {
-                    if let Err(error) = line.parse::<Checksum>() {
-                        impropers.push((i, error));
-                    }
+            let mut data = Vec::new();
+            open_input(path)?
+                .read_to_end(&mut data)
+                .with_context(|| format!("Failed to read bytes from {}", path.display()))?;
+            // A metainfo file of BitTorrent is verified piece by piece.
+            let (result, impropers) = if let Ok(torrent) = Torrent::from_bytes(&data) {
+                if opt.progress {
+                    eprintln!("Verifying the pieces of {}", path.display());
                 }
-                str.lines().flat_map(str::parse).collect()
-            };
-            let impropers = impropers;
-
-            let checksums: Vec<_> = checksums
-                .into_iter()
-                .map(|c| Checksum {
-                    algorithm: opt.hash_algorithm.or(c.algorithm),
-                    file: c.file,
-                    digest: c.digest,
-                })
-                .collect();
-            ensure!(
-                checksums.iter().all(|c| c.algorithm.is_some()),
-                "Unable to determine hash algorithm"
-            );
+                let result = torrent
+                    .verify(Path::new(""), &filter)
+                    .with_context(|| format!("Failed to verify {}", path.display()))?;
 
-            let pb = ProgressBar::new(
-                checksums
-                    .len()
-                    .try_into()
-                    .expect("Number of files exceeds the limit"),
-            )
-            .with_style(ProgressStyle::default_bar().template(PROGRESS_BAR_TEMPLATE));
+                (result, Vec::new())
+            } else {
+                let (checksums, impropers) = read_checksums(&data, path, algorithm, opt.encoding);
 
-            if opt.progress {
-                eprintln!(
-                    "Verifying {} checksums from {}",
-                    checksums.len(),
-                    path.display()
+                let checksums: Vec<_> = checksums
+                    .into_iter()
+                    .filter(|c| filter.is_match(&c.file))
+                    .map(|c| {
+                        let algorithm = match algorithm.or(c.algorithm) {
+                            Some(a) if opt.git && !a.git => Some(Algorithm::git(a.hash)?),
+                            Some(a) if opt.torrent && !a.torrent => {
+                                Some(Algorithm::torrent(a.hash)?)
+                            }
+                            Some(a) if opt.tree && !a.tree => Some(a.into_tree(opt.executable)),
+                            a => a.map(|a| Algorithm {
+                                hmac: a.hmac || opt.hmac,
+                                ..a
+                            }),
+                        };
+
+                        Ok(Checksum { algorithm, ..c })
+                    })
+                    .collect::<Result<_>>()?;
+                ensure!(
+                    checksums.iter().all(|c| c.algorithm.is_some()),
+                    "Unable to determine hash algorithm"
                 );
-            }
-            let result = if opt.progress {
-                checksums
-                    .par_iter()
-                    .progress_with(pb)
-                    .map(|c| Verify::check(c).context("Failed to verify a checksum"))
-                    .collect::<Result<Vec<_>>>()?
-            } else {
-                checksums
-                    .par_iter()
-                    .map(|c| Verify::check(c).context("Failed to verify a checksum"))
-                    .collect::<Result<Vec<_>>>()?
+
+                let pb = ProgressBar::new(
+                    checksums
+                        .len()
+                        .try_into()
+                        .expect("Number of files exceeds the limit"),
+                )
+                .with_style(ProgressStyle::default_bar().template(PROGRESS_BAR_TEMPLATE));
+
+                if opt.progress {
+                    eprintln!(
+                        "Verifying {} checksums from {}",
+                        checksums.len(),
+                        path.display()
+                    );
+                }
+                let result = if opt.progress {
+                    checksums
+                        .par_iter()
+                        .progress_with(pb)
+                        .map(|c| {
+                            Verify::check(c, &params, &filter)
+                                .context("Failed to verify a checksum")
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                } else {
+                    checksums
+                        .par_iter()
+                        .map(|c| {
+                            Verify::check(c, &params, &filter)
+                                .context("Failed to verify a checksum")
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                };
+
+                (result, impropers)
             };
 
             let result: Vec<_> = if opt.ignore_missing {
@@ -320,9 +396,127 @@ fn main() -> Result<()> {
             std::process::exit(exitcode::SOFTWARE);
         }
     } else {
-        let algo = opt
-            .hash_algorithm
-            .context("Unable to determine hash algorithm")?;
+        let mut algorithms = Vec::with_capacity(opt.hash_algorithm.len());
+        for &hash in &opt.hash_algorithm {
+            let algorithm = new_algorithm(&opt, hash)?;
+            if !algorithms.contains(&algorithm) {
+                algorithms.push(algorithm);
+            }
+        }
+        // The plain hash algorithms in the template are affected by the
+        // options in the same way as `--hash-algorithm`.
+        let template = opt
+            .format
+            .as_deref()
+            .map(str::parse::<Template>)
+            .transpose()
+            .context("Invalid template")?
+            .map(|t| {
+                t.map_algorithms(|a| {
+                    if a == a.hash.into() {
+                        new_algorithm(&opt, a.hash)
+                    } else {
+                        Ok(a)
+                    }
+                })
+            })
+            .transpose()?;
+        // The message digests in the template are computed unless the hash
+        // algorithms are specified.
+        if let Some(ref template) = template {
+            if algorithms.is_empty() {
+                algorithms = template.algorithms();
+            }
+            for algorithm in template.algorithms() {
+                ensure!(
+                    algorithms.contains(&algorithm),
+                    "{} in the template is not specified by `--hash-algorithm`",
+                    algorithm
+                );
+            }
+        }
+        let known = opt
+            .audit
+            .as_ref()
+            .map(|file| read_known(file, &algorithms, opt.encoding))
+            .transpose()?;
+        // The inputs are audited with the hash algorithms of the known files
+        // unless they are specified.
+        if let (true, Some(known)) = (algorithms.is_empty(), &known) {
+            for algorithm in known.iter().filter_map(|c| c.algorithm) {
+                if !algorithms.contains(&algorithm) {
+                    algorithms.push(algorithm);
+                }
+            }
+        }
+        let algorithms = algorithms;
+        ensure!(!algorithms.is_empty(), "Unable to determine hash algorithm");
+        let shared = known
+            .as_ref()
+            .map(|k| Audit::shared_algorithms(k, &algorithms))
+            .transpose()?;
+        ensure!(
+            opt.export_tree.is_none() || algorithms.contains(&HashAlgorithm::Tth.into()),
+            "Specify TTH to export the Merkle trees"
+        );
+        ensure!(
+            opt.export_torrent.is_none() || algorithms.len() == 1,
+            "Specify only one hash algorithm to export the torrents"
+        );
+        // The options are checked before reading the inputs, which can take a
+        // long time.
+        ensure!(
+            opt.style != Style::Sfv || algorithms == [HashAlgorithm::Crc32.into()],
+            "The SFV style supports only CRC32"
+        );
+
+        let format = DigestFormat {
+            multiformat: if opt.multihash {
+                Some(Multiformat::Multihash)
+            } else if opt.cid {
+                Some(Multiformat::Cid)
+            } else {
+                None
+            },
+            multibase: opt.multibase,
+            encoding: opt.encoding,
+        };
+        ensure!(
+            opt.style != Style::Sfv || format.multiformat.is_none() && format.multibase.is_none(),
+            "The SFV style does not support the multibase"
+        );
+        if format.multiformat.is_some() {
+            for algorithm in &algorithms {
+                ensure!(
+                    algorithm.multicodec().is_some(),
+                    "{} is not supported by multihash",
+                    algorithm
+                );
+            }
+        }
+        ensure!(
+            opt.style != Style::Hashdeep || format.is_default(),
+            "The hashdeep style supports only hexadecimal"
+        );
+        if opt.style == Style::Sri {
+            ensure!(format.is_default(), "The SRI style supports only base64");
+            ensure!(
+                template.is_some()
+                    || opt.audit.is_some()
+                    || algorithms.iter().all(|a| a.sri_name().is_some()),
+                "The SRI style supports only SHA-256, SHA-384 and SHA-512"
+            );
+        }
+        #[cfg(feature = "md4")]
+        if opt.style == Style::Ed2k {
+            ensure!(
+                format.is_default(),
+                "The ed2k style supports only the default encoding"
+            );
+            if template.is_none() && opt.audit.is_none() {
+                Algorithm::check_ed2k(&algorithms)?;
+            }
+        }
 
         let pb = ProgressBar::new(
             inputs
@@ -335,36 +529,177 @@ fn main() -> Result<()> {
         if opt.progress {
             eprintln!("Computing {} files", inputs.len());
         }
-        let checksums: Vec<_> = if opt.progress {
+        let digest = |input: &PathBuf| {
+            if opt.export_tree.is_some() {
+                digest_input_with_tree(&algorithms, &params, input)
+                    .map(|(checksums, tree)| (checksums, Some(Export::Tree(tree))))
+            } else if opt.export_torrent.is_some() {
+                digest_input_with_torrent(&algorithms, &params, &filter, input)
+                    .map(|(checksums, torrent)| (checksums, Some(Export::Torrent(torrent))))
+            } else {
+                digest_input(&algorithms, &params, &filter, input).map(|c| (c, None))
+            }
+        };
+        let checksums = if opt.progress {
             inputs
                 .par_iter()
                 .progress_with(pb)
-                .map(|i| Checksum::digest(algo, &i))
-                .collect()
+                .map(digest)
+                .collect::<Result<Vec<_>>>()?
         } else {
-            inputs
-                .par_iter()
-                .map(|i| Checksum::digest(algo, &i))
-                .collect()
+            inputs.par_iter().map(digest).collect::<Result<Vec<_>>>()?
         };
         if opt.progress {
             eprintln!("Done.");
         }
+        let (checksums, exports): (Vec<_>, Vec<_>) = checksums.into_iter().unzip();
+        let (checksums, lengths): (Vec<_>, Vec<_>) = checksums.into_iter().unzip();
+        total_length += lengths.iter().sum::<u64>();
+
+        if let Some(ref file) = opt.export_tree {
+            let trees: Vec<_> = exports
+                .iter()
+                .filter_map(|e| match e {
+                    Some(Export::Tree(tree)) => Some(tree),
+                    _ => None,
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&trees)
+                .context("Failed to serialize to a JSON string")?;
+            fs::write(file, format!("{}\n", json))
+                .with_context(|| format!("Failed to write to {}", file.display()))?;
+        }
+
+        if let Some(ref dir) = opt.export_torrent {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+            for export in &exports {
+                if let Some(Export::Torrent(torrent)) = export {
+                    let file = dir.join(format!("{}.torrent", torrent.name()));
+                    fs::write(&file, torrent.to_bytes())
+                        .with_context(|| format!("Failed to write to {}", file.display()))?;
+                }
+            }
+        }
+
+        if let (Some(known), Some(shared), Some(file)) = (known, shared, &opt.audit) {
+            let results = Audit::check(&known, &shared, &checksums, &lengths);
+            let count = |status: fn(&AuditStatus) -> bool| {
+                results.iter().filter(|r| status(&r.status)).count()
+            };
+            let (matched, moved, new, missing) = (
+                count(|s| *s == AuditStatus::Matched),
+                count(|s| matches!(s, AuditStatus::Moved(_))),
+                count(|s| *s == AuditStatus::New),
+                count(|s| *s == AuditStatus::Missing),
+            );
 
-        let output = if opt.style == Style::Json {
-            serde_json::to_string_pretty(&checksums)
-                .context("Failed to serialize to a JSON string")?
+            eprintln!("Result of audit against {}", file.display());
+            eprintln!("{}", "-".repeat(VERIFICATION_RESULT_WIDTH));
+            results
+                .iter()
+                .map(Audit::output)
+                .for_each(|o| println!("{}", o));
+            eprintln!("{}", "-".repeat(VERIFICATION_RESULT_WIDTH));
+            if matched == results.len() {
+                eprintln!("Audit passed");
+            } else {
+                eprintln!(
+                    "Audit failed (Matched:{}; Moved:{}; New:{}; Missing:{})",
+                    matched, moved, new, missing
+                );
+                is_audit_failed = true;
+            }
+        } else if let (Style::Gnu, true, None, Some(dir)) =
+            (opt.style, algorithms.len() > 1, &template, &opt.output)
+        {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+            for (i, algorithm) in algorithms.iter().enumerate() {
+                let output: Vec<u8> = checksums
+                    .iter()
+                    .flat_map(|c| {
+                        [c[i].output(opt.style, format, opt.binary), vec![b'\n']].concat()
+                    })
+                    .collect();
+                let file = dir.join(algorithm.sums_file_name());
+                fs::write(&file, output)
+                    .with_context(|| format!("Failed to write to {}", file.display()))?;
+            }
         } else {
-            checksums
-                .into_iter()
-                .map(|c| c.output(opt.style))
-                .collect::<Vec<_>>()
-                .join("\n")
-        };
-        match opt.output {
-            Some(ref file) => fs::write(file, format!("{}\n", output))
-                .with_context(|| format!("Failed to write to {}", file.display()))?,
-            None => println!("{}", output),
+            // The GNU-style lines of multiple hash algorithms cannot be told
+            // apart, so they are output in the BSD style.
+            let style = if opt.style == Style::Gnu && algorithms.len() > 1 {
+                Style::Bsd
+            } else {
+                opt.style
+            };
+            let output = if let Some(ref template) = template {
+                checksums
+                    .iter()
+                    .zip(&lengths)
+                    .flat_map(|(c, &length)| template.render(c, length, format))
+                    .collect()
+            } else {
+                match opt.style {
+                    Style::Json => {
+                        let json = if algorithms.len() > 1 {
+                            let checksums: Vec<_> = checksums
+                                .iter()
+                                .map(|c| Checksum::to_json_multi(c, format))
+                                .collect();
+                            serde_json::to_string_pretty(&checksums)
+                        } else {
+                            let checksums: Vec<_> = checksums
+                                .iter()
+                                .flatten()
+                                .map(|c| c.to_json(format))
+                                .collect();
+                            serde_json::to_string_pretty(&checksums)
+                        }
+                        .context("Failed to serialize to a JSON string")?;
+                        format!("{}\n", json).into_bytes()
+                    }
+                    #[cfg(feature = "md4")]
+                    Style::Ed2k => checksums
+                        .iter()
+                        .zip(lengths)
+                        .map(|(c, length)| {
+                            Checksum::ed2k_link(c, length).map(|l| format!("{}\n", l))
+                        })
+                        .collect::<Result<String>>()?
+                        .into_bytes(),
+                    Style::Hashdeep => Checksum::hashdeep(&algorithms, &checksums, &lengths),
+                    Style::Sri => checksums
+                        .iter()
+                        .map(|c| {
+                            Checksum::integrity(c).map(|i| {
+                                [
+                                    format!("{}  ", i).into_bytes(),
+                                    c[0].file_bytes(),
+                                    vec![b'\n'],
+                                ]
+                                .concat()
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?
+                        .concat(),
+                    _ => checksums
+                        .into_iter()
+                        .flatten()
+                        .flat_map(|c| [c.output(style, format, opt.binary), vec![b'\n']].concat())
+                        .collect(),
+                }
+            };
+            match opt.output {
+                Some(ref file) => fs::write(file, output)
+                    .with_context(|| format!("Failed to write to {}", file.display()))?,
+                None => io::stdout()
+                    .write_all(&output)
+                    .context("Failed to write to stdout")?,
+            }
         }
     }
     let total_length = total_length;
@@ -391,25 +726,243 @@ fn main() -> Result<()> {
                     BinaryBytes((total_length as f64 / duration.as_secs_f64()) as u64),
                 );
             }
+        } else if duration.is_zero() {
+            eprintln!("Computed {}", BinaryBytes(total_length));
         } else {
-            let length: u64 = inputs
-                .into_iter()
-                .map(|i| i.1)
-                .map(|d| u64::try_from(d.len()).expect("File size exceeds the limit"))
-                .sum();
-
-            if duration.is_zero() {
-                eprintln!("Computed {}", BinaryBytes(length));
-            } else {
-                eprintln!(
-                    "Computed {} in {} ({}/s)",
-                    BinaryBytes(length),
-                    humantime::format_duration(duration),
-                    BinaryBytes((length as f64 / duration.as_secs_f64()) as u64),
-                );
-            }
+            eprintln!(
+                "Computed {} in {} ({}/s)",
+                BinaryBytes(total_length),
+                humantime::format_duration(duration),
+                BinaryBytes((total_length as f64 / duration.as_secs_f64()) as u64),
+            );
         }
     }
 
+    if is_audit_failed {
+        std::process::exit(exitcode::SOFTWARE);
+    }
+
     Ok(())
 }
+
+/// Read the checksums from the contents of a checksum file.
+///
+/// Returns the checksums and the improperly formatted lines with the errors.
+fn read_checksums(
+    data: &[u8],
+    path: &Path,
+    algorithm: Option<Algorithm>,
+    encoding: Option<Encoding>,
+) -> (Vec<Checksum>, Vec<ImproperLine>) {
+    if let Ok(str) = str::from_utf8(data) {
+        // The files referred by an HTML document are relative to it.
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        if let Some(checksums) = Checksum::from_html(str, dir) {
+            return (checksums, Vec::new());
+        }
+        if let Some(result) = Checksum::from_hashdeep(str) {
+            return result;
+        }
+        if let Ok(checksums) = serde_json::from_str(str) {
+            return (checksums, Vec::new());
+        }
+        if let Ok(checksums) = serde_json::from_str::<Vec<MultiChecksum>>(str) {
+            return (
+                checksums.into_iter().flat_map(Vec::from).collect(),
+                Vec::new(),
+            );
+        }
+    }
+
+    let mut impropers = Vec::new();
+    // The lines are split as bytes, since a file name may not be valid UTF-8.
+    // Lines starting with `;` are comments in SFV files.
+    let lines = data
+        .split_inclusive(|&b| b == b'\n')
+        .map(|l| l.strip_suffix(b"\n").unwrap_or(l))
+        .map(|l| l.strip_suffix(b"\r").unwrap_or(l))
+        .enumerate()
+        .filter(|(_, l)| !l.starts_with(b";"));
+    let parse = |line| Checksum::parse(line, algorithm, encoding);
+    for (i, line) in lines.clone() {
+        if let Err(error) = parse(line) {
+            impropers.push((i, error));
+        }
+    }
+
+    (lines.flat_map(|(_, l)| parse(l)).collect(), impropers)
+}
+
+/// Read the known checksums to audit the inputs against.
+///
+/// If only one hash algorithm is specified, it is used for the checksums
+/// without the hash algorithm.
+fn read_known(
+    path: &Path,
+    algorithms: &[Algorithm],
+    encoding: Option<Encoding>,
+) -> Result<Vec<Checksum>> {
+    let mut data = Vec::new();
+    open_input(path)?
+        .read_to_end(&mut data)
+        .with_context(|| format!("Failed to read bytes from {}", path.display()))?;
+    let algorithm = match *algorithms {
+        [algorithm] => Some(algorithm),
+        _ => None,
+    };
+    let (checksums, impropers) = read_checksums(&data, path, algorithm, encoding);
+    if let Some((i, error)) = impropers.into_iter().next() {
+        return Err(error.context(format!("{}: {}", path.display(), i + 1)));
+    }
+    ensure!(
+        checksums
+            .iter()
+            .all(|c| c.algorithm.or(algorithm).is_some()),
+        "Unable to determine hash algorithm"
+    );
+
+    Ok(checksums
+        .into_iter()
+        .map(|c| Checksum {
+            algorithm: c.algorithm.or(algorithm),
+            ..c
+        })
+        .collect())
+}
+
+/// Create the algorithm for the hash algorithm from the options.
+fn new_algorithm(opt: &Opt, hash: HashAlgorithm) -> Result<Algorithm> {
+    if opt.git {
+        Algorithm::git(hash)
+    } else if opt.torrent {
+        Algorithm::torrent(hash)
+    } else if opt.tree {
+        Algorithm::new(hash, opt.hmac, opt.length).map(|a| a.into_tree(opt.executable))
+    } else {
+        Algorithm::new(hash, opt.hmac, opt.length)
+    }
+}
+
+/// Open the input for reading.
+///
+/// If the path is `-`, the input is read from stdin.
+fn open_input(path: &Path) -> Result<Box<dyn Read>> {
+    if path == Path::new("-") {
+        if atty::is(atty::Stream::Stdin) {
+            let input = dialoguer::Input::<String>::with_theme(&ColorfulTheme::default())
+                .with_prompt("Input")
+                .interact()
+                .context("Failed to read a string from stdin")?;
+
+            return Ok(Box::new(Cursor::new(input.into_bytes())));
+        }
+
+        return Ok(Box::new(io::stdin()));
+    }
+
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+    Ok(Box::new(file))
+}
+
+/// The data exported in addition to the checksums of an input.
+enum Export {
+    /// The full Merkle tree of TTH.
+    Tree(TthTree),
+    /// The torrent of the input.
+    Torrent(Torrent),
+}
+
+/// Compute message digests of the input and build the full Merkle tree of TTH
+/// in the same pass.
+///
+/// TTH is computed by the hasher which records the leaves, and the other hash
+/// algorithms are computed from the same data.
+fn digest_input_with_tree(
+    algorithms: &[Algorithm],
+    params: &Params,
+    path: &Path,
+) -> Result<((Vec<Checksum>, u64), TthTree)> {
+    let tth = Algorithm::from(HashAlgorithm::Tth);
+    let others: Vec<_> = algorithms.iter().copied().filter(|&a| a != tth).collect();
+
+    let mut hasher = Tth::with_leaves();
+    let reader = TreeReader::new(open_input(path)?, &mut hasher);
+    let (mut checksums, length) = Checksum::digest_reader(&others, params, path, reader)
+        .with_context(|| format!("Failed to compute message digests of {}", path.display()))?;
+    let (root, tree) = TthTree::new(path, hasher);
+
+    let index = algorithms
+        .iter()
+        .position(|&a| a == tth)
+        .expect("TTH should be specified");
+    checksums.insert(
+        index,
+        Checksum {
+            algorithm: Some(tth),
+            file: path.to_path_buf(),
+            digest: root,
+            size: None,
+            alternatives: Vec::new(),
+        },
+    );
+
+    Ok(((checksums, length), tree))
+}
+
+/// Compute the info-hash of the input and create its torrent in the same
+/// pass.
+///
+/// Only one hash algorithm is supported, since the torrent is named after the
+/// input.
+fn digest_input_with_torrent(
+    algorithms: &[Algorithm],
+    params: &Params,
+    filter: &Filter,
+    path: &Path,
+) -> Result<((Vec<Checksum>, u64), Torrent)> {
+    let (mut torrents, length) = Torrent::new(
+        path,
+        algorithms,
+        params.piece_length.unwrap_or(DEFAULT_PIECE_LENGTH),
+        filter,
+    )
+    .with_context(|| format!("Failed to create the torrent of {}", path.display()))?;
+    let checksums = Checksum::from_torrents(path, &torrents);
+
+    Ok(((checksums, length), torrents.remove(0)))
+}
+
+/// Compute message digests of the input without reading it into memory at
+/// once.
+///
+/// If the git object IDs or the info-hashes are computed, a directory is also
+/// accepted as the input. The tree digests are computed only for directories.
+fn digest_input(
+    algorithms: &[Algorithm],
+    params: &Params,
+    filter: &Filter,
+    path: &Path,
+) -> Result<(Vec<Checksum>, u64)> {
+    if algorithms.iter().any(|a| a.git) {
+        return if path == Path::new("-") {
+            Checksum::git_stdin(algorithms, open_input(path)?)
+        } else {
+            Checksum::git_object(algorithms, path, filter)
+        }
+        .with_context(|| format!("Failed to compute git object IDs of {}", path.display()));
+    }
+    if algorithms.iter().any(|a| a.torrent) {
+        return Checksum::info_hash(algorithms, params, path, filter)
+            .with_context(|| format!("Failed to compute info-hashes of {}", path.display()));
+    }
+    if algorithms.iter().any(|a| a.tree) {
+        return Checksum::tree_digest(algorithms, params, path, filter)
+            .with_context(|| format!("Failed to compute tree digests of {}", path.display()));
+    }
+
+    let reader = open_input(path)?;
+
+    Checksum::digest_reader(algorithms, params, path, reader)
+        .with_context(|| format!("Failed to compute message digests of {}", path.display()))
+}
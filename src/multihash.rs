@@ -0,0 +1,327 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use clap::ArgEnum;
+use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD, BASE64_NOPAD};
+
+use crate::value::{Algorithm, Checksum, HashAlgorithm, Multibase};
+
+/// The version of CIDs.
+const CID_VERSION: u64 = 1;
+/// The multicodec code of raw binary, which is the content type of CIDs.
+const RAW: u64 = 0x55;
+/// The multicodec codes of BLAKE2b and BLAKE2s with a 0-bit output. The code
+/// for each output length is the sum of it and the length in bytes.
+const BLAKE2B: u64 = 0xb200;
+const BLAKE2S: u64 = 0xb240;
+/// The maximum number of bytes of an unsigned varint.
+const MAX_VARINT_LEN: usize = 9;
+
+impl HashAlgorithm {
+    /// Get the multicodec code of the hash algorithm with the default output
+    /// length.
+    ///
+    /// Returns `None` if the hash algorithm is not registered in the
+    /// multicodec table.
+    const fn multicodec(self) -> Option<u64> {
+        let code = match self {
+            #[cfg(feature = "sha-1")]
+            Self::Sha1 => 0x11,
+            Self::Sha256 => 0x12,
+            Self::Sha512 => 0x13,
+            Self::Sha3_512 => 0x14,
+            Self::Sha3_384 => 0x15,
+            Self::Sha3_256 => 0x16,
+            Self::Sha3_224 => 0x17,
+            Self::Shake128 => 0x18,
+            Self::Shake256 => 0x19,
+            Self::Keccak224 => 0x1a,
+            Self::Keccak256 => 0x1b,
+            Self::Keccak384 => 0x1c,
+            Self::Keccak512 => 0x1d,
+            Self::Blake3 => 0x1e,
+            Self::Sha384 => 0x20,
+            #[cfg(feature = "md4")]
+            Self::Md4 => 0xd4,
+            #[cfg(feature = "md-5")]
+            Self::Md5 => 0xd5,
+            Self::Crc32 => 0x0132,
+            Self::Sha224 => 0x1013,
+            Self::Sha512_224 => 0x1014,
+            Self::Sha512_256 => 0x1015,
+            Self::Ripemd160 => 0x1053,
+            Self::Ripemd256 => 0x1054,
+            Self::Ripemd320 => 0x1055,
+            Self::K12 => 0x1d01,
+            Self::Sm3 => 0x534d,
+            Self::Blake2b => BLAKE2B + 64,
+            Self::Blake2s => BLAKE2S + 32,
+            #[cfg(feature = "skein")]
+            Self::Skein256 => 0xb320,
+            #[cfg(feature = "skein")]
+            Self::Skein512 => 0xb360,
+            #[cfg(feature = "skein")]
+            Self::Skein1024 => 0xb3e0,
+            Self::Xxh32 => 0xb3e1,
+            Self::Xxh64 => 0xb3e2,
+            Self::Xxh3_64 => 0xb3e3,
+            Self::Xxh3_128 => 0xb3e4,
+            _ => return None,
+        };
+
+        Some(code)
+    }
+}
+
+impl Algorithm {
+    /// Get the multicodec code of the algorithm.
+    ///
+    /// Returns `None` if the algorithm is not a plain message digest or the
+    /// hash algorithm is not registered in the multicodec table.
+    pub fn multicodec(self) -> Option<u64> {
+        if self.hmac || self.git || self.torrent || self.tree {
+            return None;
+        }
+
+        match (self.hash, self.length) {
+            (HashAlgorithm::Blake2b, Some(length)) => Some(BLAKE2B + (length / 8) as u64),
+            (HashAlgorithm::Blake2s, Some(length)) => Some(BLAKE2S + (length / 8) as u64),
+            (hash, _) => hash.multicodec(),
+        }
+    }
+
+    /// Get the algorithm of the multicodec code and the length of the
+    /// message digest in bytes.
+    ///
+    /// Returns `None` if the code is unknown or the length is invalid for the
+    /// hash algorithm.
+    fn from_multicodec(code: u64, len: usize) -> Option<Self> {
+        let length = Some(len * 8);
+        let algorithm = match code {
+            code if code > BLAKE2B && code <= BLAKE2B + 64 => Self::new(
+                HashAlgorithm::Blake2b,
+                false,
+                Some((code - BLAKE2B) as usize * 8),
+            )
+            .ok()?,
+            code if code > BLAKE2S && code <= BLAKE2S + 32 => Self::new(
+                HashAlgorithm::Blake2s,
+                false,
+                Some((code - BLAKE2S) as usize * 8),
+            )
+            .ok()?,
+            code => {
+                let hash = *HashAlgorithm::value_variants()
+                    .iter()
+                    .find(|h| h.multicodec() == Some(code))?;
+                if hash.default_length().is_some() {
+                    // The extendable-output functions can have any length.
+                    return Self::new(hash, false, length).ok();
+                }
+                hash.into()
+            }
+        };
+
+        Some(algorithm).filter(|a| a.digest_len() == len)
+    }
+}
+
+impl Checksum {
+    /// Wrap the message digest in a multihash.
+    ///
+    /// Returns `None` if the algorithm does not have the multicodec code.
+    pub fn multihash(&self) -> Option<Vec<u8>> {
+        let code = self.algorithm?.multicodec()?;
+
+        let mut multihash = encode_varint(code);
+        multihash.extend(encode_varint(self.digest.len() as u64));
+        multihash.extend(&self.digest);
+
+        Some(multihash)
+    }
+
+    /// Wrap the message digest in a CIDv1 of raw binary.
+    ///
+    /// Returns `None` if the algorithm does not have the multicodec code.
+    pub fn cid(&self) -> Option<Vec<u8>> {
+        let mut cid = encode_varint(CID_VERSION);
+        cid.extend(encode_varint(RAW));
+        cid.extend(self.multihash()?);
+
+        Some(cid)
+    }
+}
+
+impl Multibase {
+    /// Encode the data with the prefix of the multibase.
+    pub fn encode(self, data: &[u8]) -> String {
+        match self {
+            Self::Base16 => format!("f{}", hex::encode(data)),
+            Self::Base32 => format!("b{}", BASE32_NOPAD.encode(data).to_ascii_lowercase()),
+            Self::Base58btc => format!("z{}", bs58::encode(data).into_string()),
+            Self::Base64 => format!("m{}", BASE64_NOPAD.encode(data)),
+            Self::Base64url => format!("u{}", BASE64URL_NOPAD.encode(data)),
+        }
+    }
+
+    /// Decode the string with the prefix of the multibase.
+    ///
+    /// The uppercase variants of base16 and base32 are also accepted.
+    pub fn decode(str: &str) -> Option<Vec<u8>> {
+        let data = str.get(1..)?;
+        match str.chars().next()? {
+            'f' if !data.bytes().any(|b| b.is_ascii_uppercase()) => hex::decode(data).ok(),
+            'F' if !data.bytes().any(|b| b.is_ascii_lowercase()) => hex::decode(data).ok(),
+            'b' if !data.bytes().any(|b| b.is_ascii_uppercase()) => BASE32_NOPAD
+                .decode(data.to_ascii_uppercase().as_bytes())
+                .ok(),
+            'B' => BASE32_NOPAD.decode(data.as_bytes()).ok(),
+            'z' => bs58::decode(data).into_vec().ok(),
+            'm' => BASE64_NOPAD.decode(data.as_bytes()).ok(),
+            'u' => BASE64URL_NOPAD.decode(data.as_bytes()).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Decode the multibase-encoded multihash or CIDv1 of raw binary.
+///
+/// Returns the algorithm inferred from the multicodec code and the message
+/// digest, or `None` if the string is not a valid multihash or CID of a
+/// known hash algorithm.
+pub fn decode(str: &str) -> Option<(Algorithm, Vec<u8>)> {
+    let data = Multibase::decode(str)?;
+    let (code, rest) = decode_varint(&data)?;
+    let multihash = if code == CID_VERSION {
+        let (codec, multihash) = decode_varint(rest)?;
+        if codec != RAW {
+            return None;
+        }
+        multihash
+    } else {
+        &data
+    };
+
+    let (code, rest) = decode_varint(multihash)?;
+    let (len, digest) = decode_varint(rest)?;
+    if usize::try_from(len).ok()? != digest.len() {
+        return None;
+    }
+
+    Some((
+        Algorithm::from_multicodec(code, digest.len())?,
+        digest.to_vec(),
+    ))
+}
+
+/// Encode the integer as an unsigned varint.
+fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(MAX_VARINT_LEN);
+    while value >= 0x80 {
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+
+    buf
+}
+
+/// Decode an unsigned varint from the beginning of the data.
+///
+/// Returns the integer and the rest of the data, or `None` if the varint is
+/// invalid or not minimally encoded.
+fn decode_varint(data: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = u64::default();
+    for (i, &byte) in data.iter().take(MAX_VARINT_LEN).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return None;
+            }
+
+            return Some((value, &data[i + 1..]));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint() {
+        assert_eq!(encode_varint(0x12), [0x12]);
+        assert_eq!(encode_varint(0xb220), [0xa0, 0xe4, 0x02]);
+        assert_eq!(
+            decode_varint(&[0xa0, 0xe4, 0x02, 0x20]),
+            Some((0xb220, &[0x20][..]))
+        );
+        assert_eq!(decode_varint(&[0x80, 0x00]), None);
+        assert_eq!(decode_varint(&[0x80]), None);
+    }
+
+    #[test]
+    fn multihash() {
+        let checksum = Checksum::digest(HashAlgorithm::Sha256, &("-", b"multihash"));
+        assert_eq!(
+            hex::encode(checksum.multihash().unwrap()),
+            "12209cbc07c3f991725836a3aa2a581ca2029198aa420b9d99bc0e131d9f3e2cbe47"
+        );
+
+        let multihash = Multibase::Base58btc.encode(&checksum.multihash().unwrap());
+        assert_eq!(
+            decode(&multihash),
+            Some((HashAlgorithm::Sha256.into(), checksum.digest.clone()))
+        );
+
+        let algorithm = Algorithm::new(HashAlgorithm::Blake2b, false, Some(256)).unwrap();
+        let checksum = Checksum::digest(algorithm, &("-", b""));
+        assert_eq!(checksum.multihash().unwrap()[..4], [0xa0, 0xe4, 0x02, 0x20]);
+        assert_eq!(
+            decode(&Multibase::Base16.encode(&checksum.multihash().unwrap())),
+            Some((algorithm, checksum.digest))
+        );
+
+        let algorithm = Algorithm::new(HashAlgorithm::Shake128, false, Some(128)).unwrap();
+        let checksum = Checksum::digest(algorithm, &("-", b""));
+        assert_eq!(
+            decode(&Multibase::Base32.encode(&checksum.multihash().unwrap())),
+            Some((algorithm, checksum.digest))
+        );
+
+        assert!(Checksum::digest(HashAlgorithm::Tth, &("-", b""))
+            .multihash()
+            .is_none());
+        // The length of SHA-256 must be 32 bytes.
+        assert_eq!(decode("f12021234"), None);
+    }
+
+    #[test]
+    fn cid() {
+        let checksum = Checksum::digest(HashAlgorithm::Sha256, &("-", b"hello world"));
+        let cid = Multibase::Base32.encode(&checksum.cid().unwrap());
+        assert_eq!(
+            cid,
+            "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"
+        );
+        assert_eq!(
+            decode(&cid),
+            Some((HashAlgorithm::Sha256.into(), checksum.digest.clone()))
+        );
+        assert_eq!(decode(&cid.to_ascii_uppercase()), decode(&cid));
+        assert_eq!(
+            decode(&Multibase::Base58btc.encode(&checksum.cid().unwrap())),
+            decode(&cid)
+        );
+        // The CIDs of DAG-PB are not the digests of the contents.
+        assert_eq!(
+            decode("bafybeifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"),
+            None
+        );
+    }
+}
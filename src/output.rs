@@ -4,24 +4,218 @@
 // Copyright (C) 2021 Shun Sakai
 //
 
-use crate::value::{Checksum, Style};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use data_encoding::BASE32_NOPAD;
+use serde::Serialize;
+
+use crate::escape;
+use crate::value::{
+    Algorithm, Checksum, DigestFormat, Encoding, HashAlgorithm, Multiformat, Style,
+};
+
+/// A checksum in the JSON style.
+#[derive(Serialize)]
+pub struct JsonChecksum {
+    pub algorithm: Option<Algorithm>,
+    pub file: PathBuf,
+    pub digest: String,
+}
+
+/// The checksums of a file for multiple hash algorithms in the JSON style.
+#[derive(Serialize)]
+pub struct JsonMultiChecksum {
+    pub file: PathBuf,
+    pub digests: BTreeMap<Algorithm, String>,
+}
 
 impl Checksum {
     /// Output a checksum for the specified style.
-    pub fn output(&self, style: Style) -> String {
+    ///
+    /// The file name is output as is, so the checksum line is not always valid
+    /// UTF-8. If `binary` is `true`, the GNU style marks the file as read in
+    /// binary mode.
+    pub fn output(&self, style: Style, format: DigestFormat, binary: bool) -> Vec<u8> {
         match style {
-            Style::Sfv => format!(
-                "{}  {}",
-                hex::encode(self.digest.clone()),
-                self.file.display()
-            ),
-            Style::Bsd => format!(
-                "{} ({}) = {}",
-                self.algorithm.expect("Hash algorithm is unknown"),
-                self.file.display(),
-                hex::encode(self.digest.clone())
-            ),
+            Style::Gnu => {
+                let (prefix, file) = self.escape_file();
+                let marker = if binary { '*' } else { ' ' };
+                [
+                    format!("{}{} {}", prefix, self.encode_digest(false, format), marker)
+                        .as_bytes(),
+                    &file,
+                ]
+                .concat()
+            }
+            Style::Bsd => {
+                let (prefix, file) = self.escape_file();
+                [
+                    format!(
+                        "{}{} (",
+                        prefix,
+                        self.algorithm.expect("Hash algorithm is unknown")
+                    )
+                    .as_bytes(),
+                    &file,
+                    format!(") = {}", self.encode_digest(false, format)).as_bytes(),
+                ]
+                .concat()
+            }
+            Style::Sfv => [
+                &self.file_bytes(),
+                format!(" {}", self.encode_digest(true, format)).as_bytes(),
+            ]
+            .concat(),
             Style::Json => unreachable!(),
+            #[cfg(feature = "md4")]
+            Style::Ed2k => unreachable!(),
+            Style::Sri | Style::Hashdeep => unreachable!(),
+        }
+    }
+
+    /// Convert a checksum for the JSON style.
+    ///
+    /// The message digest is encoded in hexadecimal unless the format is
+    /// specified.
+    pub fn to_json(&self, format: DigestFormat) -> JsonChecksum {
+        JsonChecksum {
+            algorithm: self.algorithm,
+            file: self.file.clone(),
+            digest: self.encode_digest(false, format.or_hex()),
+        }
+    }
+
+    /// Convert the checksums of a file for multiple hash algorithms for the
+    /// JSON style.
+    pub fn to_json_multi(checksums: &[Self], format: DigestFormat) -> JsonMultiChecksum {
+        JsonMultiChecksum {
+            file: checksums
+                .first()
+                .map(|c| c.file.clone())
+                .unwrap_or_default(),
+            digests: checksums
+                .iter()
+                .map(|c| {
+                    (
+                        c.algorithm.expect("Hash algorithm is unknown"),
+                        c.encode_digest(false, format.or_hex()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the file name as bytes.
+    ///
+    /// On Unix, the file name is not converted, so it is output byte-exact
+    /// even if it is not valid UTF-8.
+    #[cfg(unix)]
+    pub fn file_bytes(&self) -> Vec<u8> {
+        use std::os::unix::ffi::OsStrExt;
+
+        self.file.as_os_str().as_bytes().to_vec()
+    }
+
+    /// Get the file name as bytes.
+    ///
+    /// On Unix, the file name is not converted, so it is output byte-exact
+    /// even if it is not valid UTF-8.
+    #[cfg(not(unix))]
+    pub fn file_bytes(&self) -> Vec<u8> {
+        self.file.to_string_lossy().into_owned().into_bytes()
+    }
+
+    /// Escape the file name in the same way as GNU coreutils.
+    ///
+    /// Returns the file name and the prefix of the checksum line, which is a
+    /// backslash if the file name is escaped.
+    fn escape_file(&self) -> (&'static str, Vec<u8>) {
+        let file = self.file_bytes();
+        match escape::escape(&file) {
+            Some(file) => ("\\", file),
+            None => ("", file),
+        }
+    }
+
+    /// Encode the message digest into a string.
+    ///
+    /// The multihashes and the CIDs are always encoded in the multibase. If
+    /// the format does not specify the encoding, TTH and AICH are encoded in
+    /// base32 as usual, and the others are encoded in hexadecimal.
+    pub fn encode_digest(&self, uppercase: bool, format: DigestFormat) -> String {
+        let data = match format.multiformat {
+            Some(Multiformat::Multihash) => self.multihash(),
+            Some(Multiformat::Cid) => self.cid(),
+            None => Some(self.digest.clone()),
+        }
+        .expect("Multicodec code is unknown");
+        if format.multiformat.is_some() || format.multibase.is_some() {
+            return format.multibase.unwrap_or_default().encode(&data);
+        }
+        if let Some(encoding) = format.encoding {
+            return encoding.encode(&data);
+        }
+
+        match self.algorithm.map(|a| a.hash) {
+            Some(HashAlgorithm::Tth) => BASE32_NOPAD.encode(&self.digest),
+            #[cfg(feature = "sha-1")]
+            Some(HashAlgorithm::Aich) => BASE32_NOPAD.encode(&self.digest),
+            _ if uppercase => hex::encode_upper(&self.digest),
+            _ => hex::encode(&self.digest),
+        }
+    }
+}
+
+impl DigestFormat {
+    /// Return `true` if the default encoding of the hash algorithms is used.
+    pub const fn is_default(self) -> bool {
+        self.multiformat.is_none() && self.multibase.is_none() && self.encoding.is_none()
+    }
+
+    /// Use hexadecimal if no encoding is specified, as the JSON style does.
+    fn or_hex(self) -> Self {
+        Self {
+            encoding: self.encoding.or(Some(Encoding::Hex)),
+            ..self
+        }
+    }
+}
+
+impl HashAlgorithm {
+    /// Get the name of the file to output the checksums for the hash
+    /// algorithm, such as `SHA256SUMS` or `SHA3-256SUMS`.
+    pub fn sums_file_name(self) -> String {
+        match self {
+            // Follow the name used by b2sum(1).
+            Self::Blake2b => "B2SUMS".to_string(),
+            _ => format!("{}SUMS", self.to_string().to_uppercase().replace('/', "-")),
+        }
+    }
+}
+
+impl Algorithm {
+    /// Get the name of the file to output the checksums for the algorithm,
+    /// such as `SHA256SUMS`, `HMAC-SHA256SUMS` or `BLAKE2B-256SUMS`.
+    pub fn sums_file_name(self) -> String {
+        if self.tree {
+            let prefix = if self.executable { "TREE-EXEC" } else { "TREE" };
+            let algorithm = Self {
+                tree: false,
+                executable: false,
+                ..self
+            };
+            format!("{}-{}", prefix, algorithm.sums_file_name())
+        } else if self.hmac {
+            format!("HMAC-{}", self.hash.sums_file_name())
+        } else if self.git {
+            format!("GIT-{}", self.hash.sums_file_name())
+        } else if self.torrent {
+            format!("TORRENT-{}", self.hash.sums_file_name())
+        } else if self.length.is_some() {
+            format!("{}SUMS", self.to_string().to_uppercase())
+        } else {
+            self.hash.sums_file_name()
         }
     }
 }
@@ -31,17 +225,18 @@ mod tests {
     use super::*;
 
     #[test]
-    fn sfv_style_checksum() {
+    fn gnu_style_checksum() {
         assert_eq!(
             Checksum::digest(
                 crate::value::HashAlgorithm::Blake2b,
                 &("-", b"Hello, world!")
             )
-            .output(Style::Sfv),
-            include_str!("../tests/resource/checksum/sfv.b2b")
+            .output(Style::Gnu, DigestFormat::default(), false),
+            include_str!("../tests/resource/checksum/gnu.b2b")
                 .lines()
                 .next()
                 .unwrap()
+                .as_bytes()
         );
     }
 
@@ -52,11 +247,66 @@ mod tests {
                 crate::value::HashAlgorithm::Blake2b,
                 &("-", b"Hello, world!")
             )
-            .output(Style::Bsd),
+            .output(Style::Bsd, DigestFormat::default(), false),
             include_str!("../tests/resource/checksum/bsd.b2b")
                 .lines()
                 .next()
                 .unwrap()
+                .as_bytes()
+        );
+    }
+
+    #[test]
+    fn escaped_checksum() {
+        let checksum = Checksum::digest(crate::value::HashAlgorithm::Sha256, &("a\\b\nc", b""));
+        assert_eq!(
+            checksum.output(Style::Gnu, DigestFormat::default(), true),
+            b"\\e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855 *a\\\\b\\nc"
         );
+        assert_eq!(
+            checksum.output(Style::Bsd, DigestFormat::default(), false),
+            b"\\SHA256 (a\\\\b\\nc) = \
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn tth_checksum() {
+        assert_eq!(
+            Checksum::digest(crate::value::HashAlgorithm::Tth, &("-", b"")).output(
+                Style::Bsd,
+                DigestFormat::default(),
+                false
+            ),
+            b"TTH (-) = LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ"
+        );
+    }
+
+    #[test]
+    fn sfv_style_checksum() {
+        assert_eq!(
+            Checksum::digest(
+                crate::value::HashAlgorithm::Crc32,
+                &("resource/tree/hello.txt", b"Hello, world!")
+            )
+            .output(Style::Sfv, DigestFormat::default(), false),
+            include_str!("../tests/resource/checksum/crc32.sfv")
+                .lines()
+                .nth(1)
+                .unwrap()
+                .as_bytes()
+        );
+    }
+
+    #[test]
+    fn sums_file_name() {
+        let name = |algorithm: &str| algorithm.parse::<Algorithm>().unwrap().sums_file_name();
+
+        assert_eq!(name("SHA256"), "SHA256SUMS");
+        assert_eq!(name("SHA3-256"), "SHA3-256SUMS");
+        assert_eq!(name("BLAKE2b"), "B2SUMS");
+        assert_eq!(name("BLAKE2b-256"), "BLAKE2B-256SUMS");
+        assert_eq!(name("SHA512/224"), "SHA-512-224SUMS");
+        assert_eq!(name("HMAC-SHA3-512"), "HMAC-SHA3-512SUMS");
     }
 }
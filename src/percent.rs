@@ -0,0 +1,39 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+#[cfg(feature = "md4")]
+use std::fmt::Write;
+
+/// Percent-encode the string except for the unreserved characters of URI.
+#[cfg(feature = "md4")]
+pub fn encode(str: &str) -> String {
+    str.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            write!(encoded, "%{:02X}", byte).expect("Failed to write to a string");
+        }
+        encoded
+    })
+}
+
+/// Decode the percent-encoded string.
+///
+/// Returns `None` if the string is not a valid percent-encoded UTF-8 string.
+pub fn decode(str: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(str.len());
+    let mut iter = str.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(hex::decode(hex).ok()?[0]);
+        } else {
+            bytes.push(byte);
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
@@ -0,0 +1,208 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use data_encoding::BASE64;
+
+use crate::value::{Algorithm, Checksum, HashAlgorithm};
+use crate::{percent, regex};
+
+impl Algorithm {
+    /// Get the name of the algorithm in the integrity metadata of Subresource
+    /// Integrity (SRI).
+    ///
+    /// Returns `None` if the algorithm is not supported by SRI, which supports
+    /// only SHA-256, SHA-384 and SHA-512.
+    pub fn sri_name(self) -> Option<&'static str> {
+        if self != self.hash.into() {
+            return None;
+        }
+
+        match self.hash {
+            HashAlgorithm::Sha256 => Some("sha256"),
+            HashAlgorithm::Sha384 => Some("sha384"),
+            HashAlgorithm::Sha512 => Some("sha512"),
+            _ => None,
+        }
+    }
+}
+
+impl Checksum {
+    /// Output the integrity metadata of a file from its checksums.
+    ///
+    /// The hashes are separated by a space, so the metadata can be used as is
+    /// for the `integrity` attribute.
+    pub fn integrity(checksums: &[Self]) -> Result<String> {
+        let hashes = checksums
+            .iter()
+            .map(|c| match c.algorithm.and_then(Algorithm::sri_name) {
+                Some(name) => Ok(format!("{}-{}", name, BASE64.encode(&c.digest))),
+                None => bail!("The SRI style supports only SHA-256, SHA-384 and SHA-512"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(hashes.join(" "))
+    }
+
+    /// Parse the integrity metadata as a checksum of the file.
+    ///
+    /// As browsers do, the hashes of unknown algorithms are ignored, and only
+    /// the hashes of the strongest algorithm are used. The file is valid if it
+    /// matches any of them, so the first one is the digest and the rest are
+    /// the alternatives. Returns `None` if there is no valid hash.
+    pub fn parse_integrity(integrity: &str, file: impl Into<PathBuf>) -> Option<Self> {
+        let hashes: Vec<_> = integrity
+            .split_ascii_whitespace()
+            .filter_map(|hash| {
+                let captures = regex!(
+                    r"^(?P<algorithm>sha(?:256|384|512))-(?P<digest>[[:alnum:]+/]+={0,2})(?:\?.*)?$"
+                )
+                .captures(hash)?;
+                let algorithm = match &captures["algorithm"] {
+                    "sha256" => HashAlgorithm::Sha256,
+                    "sha384" => HashAlgorithm::Sha384,
+                    _ => HashAlgorithm::Sha512,
+                };
+                let digest = BASE64.decode(captures["digest"].as_bytes()).ok()?;
+
+                Some((Algorithm::from(algorithm), digest))
+            })
+            .filter(|(algorithm, digest)| algorithm.digest_len() == digest.len())
+            .collect();
+        let algorithm = hashes.iter().map(|&(algorithm, _)| algorithm).max()?;
+        let mut digests: Vec<_> = hashes
+            .into_iter()
+            .filter(|&(a, _)| a == algorithm)
+            .map(|(_, digest)| digest)
+            .collect();
+        digests.dedup();
+        let digest = digests.remove(0);
+
+        Some(Self {
+            algorithm: Some(algorithm),
+            file: file.into(),
+            digest,
+            size: None,
+            alternatives: digests,
+        })
+    }
+
+    /// Extract the checksums from the `integrity` attributes of the
+    /// `<script>` and `<link>` elements in an HTML document.
+    ///
+    /// The URLs of the elements are resolved as the paths relative to the
+    /// directory, and the elements which refer to other hosts are skipped.
+    /// Returns `None` if the text does not look like an HTML document.
+    pub fn from_html(html: &str, dir: &Path) -> Option<Vec<Self>> {
+        if !regex!(r"(?i)<(?:!doctype\s+html|html|head|script|link)\b").is_match(html) {
+            return None;
+        }
+
+        let checksums = regex!(r"(?is)<(?:script|link)\b[^>]*>")
+            .find_iter(html)
+            .filter_map(|tag| {
+                let (mut url, mut integrity) = (None, None);
+                for captures in regex!(
+                    r#"(?i)\s(?P<name>[[:alpha:]][[:alnum:]_:-]*)\s*=\s*(?:"(?P<double>[^"]*)"|'(?P<single>[^']*)'|(?P<bare>[^\s"'=<>`]+))"#
+                )
+                .captures_iter(tag.as_str())
+                {
+                    let value = captures
+                        .name("double")
+                        .or_else(|| captures.name("single"))
+                        .or_else(|| captures.name("bare"))
+                        .map_or("", |v| v.as_str());
+                    match captures["name"].to_ascii_lowercase().as_str() {
+                        "src" | "href" => url = Some(value),
+                        "integrity" => integrity = Some(value),
+                        _ => (),
+                    }
+                }
+
+                Self::parse_integrity(integrity?, dir.join(url_to_path(url?)?))
+            })
+            .collect();
+
+        Some(checksums)
+    }
+}
+
+/// Convert the URL of a resource to the relative path of the local file.
+///
+/// Returns `None` if the URL refers to another host.
+fn url_to_path(url: &str) -> Option<PathBuf> {
+    let url = url.replace("&amp;", "&");
+    if url.starts_with("//") || regex!(r"^[[:alpha:]][[:alnum:]+.-]*:").is_match(&url) {
+        return None;
+    }
+
+    let path = url.split(|c| c == '?' || c == '#').next()?;
+    let path = percent::decode(path.trim_start_matches('/'))?;
+
+    Some(path.split('/').filter(|c| !c.is_empty()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sri_integrity() {
+        let checksums = [
+            Checksum::digest(HashAlgorithm::Sha256, &("-", b"alert('Hello, world.');")),
+            Checksum::digest(HashAlgorithm::Sha384, &("-", b"alert('Hello, world.');")),
+        ];
+        let integrity = Checksum::integrity(&checksums).unwrap();
+        assert_eq!(
+            integrity,
+            "sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng= \
+             sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO"
+        );
+
+        let checksum = Checksum::parse_integrity(&integrity, "foo.js").unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Sha384.into()));
+        assert_eq!(checksum.digest, checksums[1].digest);
+
+        assert!(
+            Checksum::integrity(&[Checksum::digest(HashAlgorithm::Sha224, &("-", b""))]).is_err()
+        );
+        assert!(Checksum::parse_integrity("md5-1B2M2Y8AsgTpgAmY7PhCfg==", "foo.js").is_none());
+    }
+
+    #[test]
+    fn rotated_integrity() {
+        let old = [Checksum::digest(HashAlgorithm::Sha384, &("-", b"old"))];
+        let new = [Checksum::digest(HashAlgorithm::Sha384, &("-", b"new"))];
+        let integrity = format!(
+            "sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng= {} {}",
+            Checksum::integrity(&old).unwrap(),
+            Checksum::integrity(&new).unwrap()
+        );
+        let checksum = Checksum::parse_integrity(&integrity, "foo.js").unwrap();
+
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Sha384.into()));
+        assert_eq!(checksum.digest, old[0].digest);
+        assert_eq!(checksum.alternatives, [new[0].digest.clone()]);
+    }
+
+    #[test]
+    fn extract_integrity() {
+        let html = r#"<!DOCTYPE html>
+<script src="js/app%20v1.js?v=1" integrity="sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng="></script>
+<link rel=stylesheet href='/css/style.css' integrity='sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO'>
+<script src="https://example.com/lib.js" integrity="sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng="></script>
+<script src="js/plain.js"></script>"#;
+        let checksums = Checksum::from_html(html, Path::new("site")).unwrap();
+
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(checksums[0].file, Path::new("site/js/app v1.js"));
+        assert_eq!(checksums[1].file, Path::new("site/css/style.css"));
+        assert_eq!(checksums[1].algorithm, Some(HashAlgorithm::Sha384.into()));
+        assert!(Checksum::from_html("SHA256 (foo) = abc", Path::new("")).is_none());
+    }
+}
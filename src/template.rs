@@ -0,0 +1,280 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::ffi::OsStr;
+use std::fs;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Error, Result};
+use clap::ArgEnum;
+
+use crate::value::{Algorithm, Checksum, DigestFormat, Encoding};
+
+/// The template to output the checksums of a file.
+#[derive(Debug, PartialEq)]
+pub struct Template(Vec<Token>);
+
+/// A part of a template.
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// The text which is output as is.
+    Literal(String),
+    /// The message digest of the hash algorithm of the line (`%h`).
+    Digest,
+    /// The message digest of the specified algorithm (`%{ALGO}`), which is
+    /// encoded in the specified encoding if any (`%{ALGO:ENCODING}`).
+    AlgorithmDigest(Algorithm, Option<Encoding>),
+    /// The name of the hash algorithm of the line (`%a`).
+    Algorithm,
+    /// The path of the file (`%p`).
+    Path,
+    /// The base name of the file (`%f`).
+    Basename,
+    /// The size of the file in bytes (`%s`).
+    Size,
+    /// The last modification time of the file in RFC 3339 (`%m`).
+    Mtime,
+}
+
+impl Template {
+    /// Get the algorithms of the message digests specified by `%{ALGO}`.
+    pub fn algorithms(&self) -> Vec<Algorithm> {
+        let mut algorithms = Vec::new();
+        for token in &self.0 {
+            if let Token::AlgorithmDigest(algorithm, _) = token {
+                if !algorithms.contains(algorithm) {
+                    algorithms.push(*algorithm);
+                }
+            }
+        }
+
+        algorithms
+    }
+
+    /// Replace the algorithms of the message digests specified by `%{ALGO}`.
+    pub fn map_algorithms(self, f: impl Fn(Algorithm) -> Result<Algorithm>) -> Result<Self> {
+        self.0
+            .into_iter()
+            .map(|token| match token {
+                Token::AlgorithmDigest(algorithm, encoding) => {
+                    Ok(Token::AlgorithmDigest(f(algorithm)?, encoding))
+                }
+                token => Ok(token),
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// Output the checksums of a file.
+    ///
+    /// If the template contains `%h` or `%a`, a line is output for each hash
+    /// algorithm. Otherwise, a line is output for the file. The lines are
+    /// output as bytes, so the file names are not converted.
+    pub fn render(&self, checksums: &[Checksum], length: u64, format: DigestFormat) -> Vec<u8> {
+        let is_per_algorithm = self
+            .0
+            .iter()
+            .any(|t| matches!(t, Token::Digest | Token::Algorithm));
+        let lines = if is_per_algorithm { checksums.len() } else { 1 };
+
+        checksums
+            .iter()
+            .take(lines)
+            .map(|checksum| {
+                let mut line: Vec<u8> = self
+                    .0
+                    .iter()
+                    .flat_map(|token| match token {
+                        Token::Literal(text) => text.clone().into_bytes(),
+                        Token::Digest => checksum.encode_digest(false, format).into_bytes(),
+                        Token::AlgorithmDigest(algorithm, encoding) => {
+                            let checksum = checksums
+                                .iter()
+                                .find(|c| c.algorithm == Some(*algorithm))
+                                .expect("Hash algorithm is not computed");
+                            match encoding {
+                                Some(encoding) => encoding.encode(&checksum.digest),
+                                None => checksum.encode_digest(false, format),
+                            }
+                            .into_bytes()
+                        }
+                        Token::Algorithm => checksum
+                            .algorithm
+                            .expect("Hash algorithm is unknown")
+                            .to_string()
+                            .into_bytes(),
+                        Token::Path => checksum.file_bytes(),
+                        Token::Basename => checksum
+                            .file
+                            .file_name()
+                            .map_or_else(|| checksum.file_bytes(), os_str_bytes),
+                        Token::Size => length.to_string().into_bytes(),
+                        // The modification time of stdin is unknown.
+                        Token::Mtime => fs::metadata(&checksum.file)
+                            .and_then(|m| m.modified())
+                            .map(|t| humantime::format_rfc3339_seconds(t).to_string())
+                            .unwrap_or_default()
+                            .into_bytes(),
+                    })
+                    .collect();
+                line.push(b'\n');
+                line
+            })
+            .collect::<Vec<_>>()
+            .concat()
+    }
+}
+
+/// Get the file name as bytes, which is not converted on Unix.
+#[cfg(unix)]
+fn os_str_bytes(name: &OsStr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    name.as_bytes().to_vec()
+}
+
+/// Get the file name as bytes, which is not converted on Unix.
+#[cfg(not(unix))]
+fn os_str_bytes(name: &OsStr) -> Vec<u8> {
+    name.to_string_lossy().into_owned().into_bytes()
+}
+
+impl FromStr for Template {
+    type Err = Error;
+
+    fn from_str(template: &str) -> Result<Self> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => {
+                    let token = match chars.next() {
+                        Some('%') => {
+                            literal.push('%');
+                            continue;
+                        }
+                        Some('h') => Token::Digest,
+                        Some('a') => Token::Algorithm,
+                        Some('p') => Token::Path,
+                        Some('f') => Token::Basename,
+                        Some('s') => Token::Size,
+                        Some('m') => Token::Mtime,
+                        Some('{') => {
+                            let rest = chars.as_str();
+                            let end = rest
+                                .find('}')
+                                .ok_or_else(|| anyhow!("Unterminated placeholder: %{{{}", rest))?;
+                            chars = rest[end + 1..].chars();
+                            parse_digest(&rest[..end])?
+                        }
+                        Some(c) => bail!("Unknown placeholder: %{}", c),
+                        None => bail!("Incomplete placeholder at the end of the template"),
+                    };
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(literal.split_off(0)));
+                    }
+                    tokens.push(token);
+                }
+                '\\' => match chars.next() {
+                    Some('n') => literal.push('\n'),
+                    Some('t') => literal.push('\t'),
+                    Some('\\') => literal.push('\\'),
+                    Some(c) => {
+                        literal.push('\\');
+                        literal.push(c);
+                    }
+                    None => literal.push('\\'),
+                },
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+
+        Ok(Self(tokens))
+    }
+}
+
+/// Parse the placeholder of the message digest of the algorithm, which is
+/// `ALGO` or `ALGO:ENCODING`.
+fn parse_digest(placeholder: &str) -> Result<Token> {
+    let (algorithm, encoding) = match placeholder.split_once(':') {
+        Some((algorithm, encoding)) => (
+            algorithm,
+            Some(
+                Encoding::from_str(encoding, true)
+                    .map_err(|_| anyhow!("Unknown encoding: {}", encoding))?,
+            ),
+        ),
+        None => (placeholder, None),
+    };
+
+    Ok(Token::AlgorithmDigest(algorithm.parse()?, encoding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::HashAlgorithm;
+
+    #[test]
+    fn parse_template() {
+        let template: Template = r"%{sha256:base64}  %p\t100%%".parse().unwrap();
+        assert_eq!(
+            template,
+            Template(vec![
+                Token::AlgorithmDigest(HashAlgorithm::Sha256.into(), Some(Encoding::Base64)),
+                Token::Literal("  ".to_string()),
+                Token::Path,
+                Token::Literal("\t100%".to_string()),
+            ])
+        );
+        assert_eq!(template.algorithms(), [HashAlgorithm::Sha256.into()]);
+
+        assert!("%x".parse::<Template>().is_err());
+        assert!("%".parse::<Template>().is_err());
+        assert!("%{unknown}".parse::<Template>().is_err());
+        assert!("%{sha256".parse::<Template>().is_err());
+        assert!("%{sha256:base16}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn render_template() {
+        let checksums = [
+            Checksum::digest(HashAlgorithm::Sha256, &("dir/foo.txt", b"Hello, world!")),
+            Checksum::digest(HashAlgorithm::Crc32, &("dir/foo.txt", b"Hello, world!")),
+        ];
+
+        let template: Template = "%f,%s,%{crc32:upper-hex}".parse().unwrap();
+        assert_eq!(
+            template.render(&checksums, 13, DigestFormat::default()),
+            b"foo.txt,13,EBE6C6E6\n"
+        );
+
+        let template: Template = "%a %h %p".parse().unwrap();
+        assert_eq!(
+            template.render(&checksums, 13, DigestFormat::default()),
+            b"SHA256 315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3 \
+             dir/foo.txt\n\
+             CRC32 ebe6c6e6 dir/foo.txt\n"
+        );
+    }
+    #[test]
+    #[cfg(unix)]
+    fn render_non_utf8_file_name() {
+        use std::{os::unix::ffi::OsStrExt, path::Path};
+
+        let file = Path::new(OsStr::from_bytes(b"dir/\xff.txt"));
+        let checksums = [Checksum::digest(HashAlgorithm::Crc32, &(file, b""))];
+        let template: Template = "%p %f".parse().unwrap();
+        assert_eq!(
+            template.render(&checksums, 0, DigestFormat::default()),
+            b"dir/\xff.txt \xff.txt\n"
+        );
+    }
+}
@@ -0,0 +1,990 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, ensure, Context, Result};
+use digest::Digest;
+#[cfg(feature = "sha-1")]
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::bencode::Value;
+use crate::value::{Algorithm, Checksum, HashAlgorithm, Params};
+use crate::verify::Verify;
+use crate::walk::{self, Filter};
+
+/// The default length of a piece of a torrent.
+pub const DEFAULT_PIECE_LENGTH: u64 = 262_144;
+/// The size of a block of BitTorrent v2, which is a leaf of the Merkle tree
+/// of a file.
+const BLOCK_SIZE: u64 = 16384;
+
+/// A node of the Merkle tree of BitTorrent v2.
+type Node = [u8; 32];
+
+/// The version of the BitTorrent protocol.
+#[derive(Clone, Copy)]
+enum Version {
+    /// BitTorrent v1 (BEP 3), which hashes the pieces with SHA-1.
+    #[cfg(feature = "sha-1")]
+    V1,
+    /// BitTorrent v2 (BEP 52), which hashes each file as a Merkle tree with
+    /// SHA-256.
+    V2,
+}
+
+impl Version {
+    fn algorithm(self) -> Algorithm {
+        let hash = match self {
+            #[cfg(feature = "sha-1")]
+            Self::V1 => HashAlgorithm::Sha1,
+            Self::V2 => HashAlgorithm::Sha256,
+        };
+
+        Algorithm::torrent(hash).expect("Hash algorithm should be supported by BitTorrent")
+    }
+}
+
+impl From<Algorithm> for Version {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm.hash {
+            #[cfg(feature = "sha-1")]
+            HashAlgorithm::Sha1 => Self::V1,
+            _ => Self::V2,
+        }
+    }
+}
+
+/// A file in a torrent.
+struct Entry {
+    /// The path of the file on the local file system.
+    path: PathBuf,
+    /// The components of the path relative to the directory of the torrent,
+    /// which are empty for a single-file torrent.
+    components: Vec<Vec<u8>>,
+    length: u64,
+    /// Whether the file is a padding file (BEP 47), which consists of zeros.
+    padding: bool,
+    /// The root of the Merkle tree of the file for BitTorrent v2.
+    root: Option<Vec<u8>>,
+}
+
+impl Entry {
+    /// Open the file as the data of the length in the torrent.
+    ///
+    /// The file is truncated or padded with zeros to the length, and a
+    /// missing file is read as zeros.
+    fn open(&self) -> io::Result<impl Read> {
+        let reader: Box<dyn Read> = if self.padding {
+            Box::new(io::empty())
+        } else {
+            match File::open(&self.path) {
+                Ok(file) => Box::new(file),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Box::new(io::empty()),
+                Err(error) => return Err(error),
+            }
+        };
+
+        Ok(reader
+            .take(self.length)
+            .chain(io::repeat(u8::default()))
+            .take(self.length))
+    }
+}
+
+/// A reader which reads the files of a torrent in order as a single stream.
+#[cfg(feature = "sha-1")]
+struct Files<'a> {
+    entries: &'a [Entry],
+    current: Option<Box<dyn Read>>,
+}
+
+#[cfg(feature = "sha-1")]
+impl<'a> Files<'a> {
+    const fn new(entries: &'a [Entry]) -> Self {
+        Self {
+            entries,
+            current: None,
+        }
+    }
+}
+
+#[cfg(feature = "sha-1")]
+impl Read for Files<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(ref mut reader) = self.current {
+                let n = reader.read(buf)?;
+                if n > 0 || buf.is_empty() {
+                    return Ok(n);
+                }
+            }
+
+            match self.entries.split_first() {
+                Some((entry, entries)) => {
+                    self.current = Some(Box::new(entry.open()?));
+                    self.entries = entries;
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// A metainfo file of BitTorrent.
+pub struct Torrent {
+    version: Version,
+    metainfo: Value,
+}
+
+impl Torrent {
+    /// Create the torrents of the file or directory for the algorithms.
+    ///
+    /// The files are read only once for all the versions of BitTorrent. The
+    /// files in a directory not selected by the filter are skipped. Returns
+    /// the torrents in the order of the algorithms and the total size of the
+    /// files.
+    pub fn new(
+        path: &Path,
+        algorithms: &[Algorithm],
+        piece_length: u64,
+        filter: &Filter,
+    ) -> Result<(Vec<Self>, u64)> {
+        ensure!(
+            path != Path::new("-"),
+            "Unable to create the torrent of stdin"
+        );
+        ensure!(
+            piece_length >= BLOCK_SIZE && piece_length.is_power_of_two(),
+            "Invalid piece length: {}",
+            piece_length
+        );
+
+        let name = name(path)?;
+        let entries = local_entries(path, filter)?;
+        let length = entries.iter().map(|e| e.length).sum();
+        let versions: Vec<_> = algorithms.iter().copied().map(Version::from).collect();
+
+        #[cfg(feature = "sha-1")]
+        let mut pieces = versions
+            .iter()
+            .any(|v| matches!(v, Version::V1))
+            .then(|| Pieces::new(piece_length));
+        let is_v2 = versions.iter().any(|v| matches!(v, Version::V2));
+        let mut trees = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let mut reader = entry.open()?;
+            let mut leaves = Vec::new();
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                let n = reader
+                    .by_ref()
+                    .take(BLOCK_SIZE)
+                    .read_to_end(&mut buf)
+                    .with_context(|| format!("Failed to read {}", entry.path.display()))?;
+                if n == 0 {
+                    break;
+                }
+
+                #[cfg(feature = "sha-1")]
+                if let Some(ref mut pieces) = pieces {
+                    pieces.update(&buf);
+                }
+                if is_v2 {
+                    leaves.push(Sha256::digest(&buf).into());
+                }
+            }
+            trees.push((!leaves.is_empty()).then(|| v2_tree(&leaves, piece_length)));
+        }
+        #[cfg(feature = "sha-1")]
+        let pieces = pieces.map(Pieces::finalize).unwrap_or_default();
+
+        let torrents = versions
+            .into_iter()
+            .map(|version| {
+                let mut info = BTreeMap::new();
+                info.insert(b"name".to_vec(), Value::from(name.clone()));
+                info.insert(b"piece length".to_vec(), to_integer(piece_length));
+                let mut metainfo = BTreeMap::new();
+                metainfo.insert(
+                    b"created by".to_vec(),
+                    Value::from(concat!("RSHash ", env!("CARGO_PKG_VERSION"))),
+                );
+
+                match version {
+                    #[cfg(feature = "sha-1")]
+                    Version::V1 => {
+                        info.insert(b"pieces".to_vec(), Value::from(pieces.concat()));
+
+                        if path.is_dir() {
+                            let files = entries
+                                .iter()
+                                .map(|e| {
+                                    let path: Vec<_> =
+                                        e.components.iter().cloned().map(Value::from).collect();
+
+                                    [
+                                        ("length", to_integer(e.length)),
+                                        ("path", Value::from(path)),
+                                    ]
+                                    .into_iter()
+                                    .collect()
+                                })
+                                .collect::<Vec<Value>>();
+                            info.insert(b"files".to_vec(), Value::from(files));
+                        } else {
+                            info.insert(b"length".to_vec(), to_integer(length));
+                        }
+                    }
+                    Version::V2 => {
+                        let mut file_tree = BTreeMap::new();
+                        let mut piece_layers = BTreeMap::new();
+                        for (entry, tree) in entries.iter().zip(&trees) {
+                            let mut file = BTreeMap::new();
+                            file.insert(b"length".to_vec(), to_integer(entry.length));
+                            if let Some((root, layer)) = tree {
+                                file.insert(b"pieces root".to_vec(), Value::from(root.to_vec()));
+                                if !layer.is_empty() {
+                                    piece_layers.insert(root.to_vec(), Value::from(layer.concat()));
+                                }
+                            }
+
+                            let components = if entry.components.is_empty() {
+                                vec![name.clone()]
+                            } else {
+                                entry.components.clone()
+                            };
+                            insert_file(&mut file_tree, &components, Value::Dict(file));
+                        }
+
+                        info.insert(b"file tree".to_vec(), Value::Dict(file_tree));
+                        info.insert(b"meta version".to_vec(), Value::from(2));
+                        metainfo.insert(b"piece layers".to_vec(), Value::Dict(piece_layers));
+                    }
+                }
+                metainfo.insert(b"info".to_vec(), Value::Dict(info));
+
+                Self {
+                    version,
+                    metainfo: Value::Dict(metainfo),
+                }
+            })
+            .collect();
+
+        Ok((torrents, length))
+    }
+
+    /// Parse the metainfo file.
+    ///
+    /// A hybrid torrent is parsed as BitTorrent v2.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let metainfo = Value::decode(data)?;
+        let info = metainfo
+            .get("info")
+            .context("The info dictionary is missing")?;
+
+        let version = if info.get("meta version").and_then(Value::as_integer) == Some(2) {
+            Version::V2
+        } else if info.get("pieces").is_some() {
+            #[cfg(feature = "sha-1")]
+            {
+                Version::V1
+            }
+            #[cfg(not(feature = "sha-1"))]
+            bail!("BitTorrent v1 is not supported because SHA-1 is disabled")
+        } else {
+            bail!("Unknown version of BitTorrent");
+        };
+
+        Ok(Self { version, metainfo })
+    }
+
+    /// Get the name of the torrent.
+    pub fn name(&self) -> String {
+        let name = self
+            .metainfo
+            .get("info")
+            .and_then(|i| i.get("name"))
+            .and_then(Value::as_bytes)
+            .unwrap_or_default();
+
+        String::from_utf8_lossy(name).into_owned()
+    }
+
+    /// Get the encoded metainfo file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.metainfo.encode()
+    }
+
+    /// Get the info-hash, which is the message digest of the info dictionary.
+    pub fn info_hash(&self) -> Vec<u8> {
+        let info = self
+            .metainfo
+            .get("info")
+            .expect("The info dictionary should exist")
+            .encode();
+
+        match self.version {
+            #[cfg(feature = "sha-1")]
+            Version::V1 => Sha1::digest(&info).to_vec(),
+            Version::V2 => Sha256::digest(&info).to_vec(),
+        }
+    }
+
+    /// Verify the files in the directory piece by piece.
+    ///
+    /// The byte ranges of the corrupted pieces are reported for each file.
+    /// The files not selected by the filter are not reported. An empty path
+    /// means the current directory.
+    pub fn verify(&self, dir: &Path, filter: &Filter) -> Result<Vec<Verify>> {
+        let info = self
+            .metainfo
+            .get("info")
+            .expect("The info dictionary should exist");
+        let piece_length = info
+            .get("piece length")
+            .and_then(Value::as_integer)
+            .and_then(|l| u64::try_from(l).ok())
+            .filter(|&l| l > 0)
+            .context("Invalid piece length")?;
+        ensure!(
+            !matches!(self.version, Version::V2)
+                || piece_length >= BLOCK_SIZE && piece_length.is_power_of_two(),
+            "Invalid piece length: {}",
+            piece_length
+        );
+        let entries = self.entries(dir)?;
+
+        let corrupted = match self.version {
+            #[cfg(feature = "sha-1")]
+            Version::V1 => {
+                let pieces = info
+                    .get("pieces")
+                    .and_then(Value::as_bytes)
+                    .filter(|p| p.len() % 20 == 0)
+                    .context("Invalid pieces")?;
+                let hashes = v1_pieces(Files::new(&entries), piece_length)?;
+                ensure!(
+                    hashes.len() == pieces.len() / 20,
+                    "The number of pieces does not match the total length of the files"
+                );
+
+                let mut offset = u64::default();
+                let failed: Vec<_> = hashes
+                    .iter()
+                    .zip(pieces.chunks(20))
+                    .enumerate()
+                    .filter(|(_, (hash, piece))| hash.as_slice() != *piece)
+                    .map(|(i, _)| piece_range(i, piece_length, u64::MAX))
+                    .collect();
+                entries
+                    .iter()
+                    .map(|e| {
+                        let range = offset..offset + e.length;
+                        offset = range.end;
+
+                        failed
+                            .iter()
+                            .filter(|p| p.start < range.end && range.start < p.end)
+                            .map(|p| {
+                                p.start.max(range.start) - range.start
+                                    ..p.end.min(range.end) - range.start
+                            })
+                            .filter(|r| !r.is_empty())
+                            .collect()
+                    })
+                    .collect()
+            }
+            Version::V2 => {
+                let piece_layers = self.metainfo.get("piece layers");
+                entries
+                    .iter()
+                    .map(|e| {
+                        let root = match e.root {
+                            Some(ref root) => root,
+                            None => return Ok(Vec::new()),
+                        };
+                        let leaves = v2_leaves(e.open()?)?;
+                        let (actual_root, layer) = v2_tree(&leaves, piece_length);
+                        // A file not larger than a piece is verified by the root.
+                        if layer.is_empty() {
+                            return Ok(if actual_root[..] == root[..] {
+                                Vec::new()
+                            } else {
+                                vec![piece_range(0, piece_length, e.length)]
+                            });
+                        }
+
+                        let expected = piece_layers
+                            .and_then(|l| l.as_dict())
+                            .and_then(|l| l.get(root))
+                            .and_then(Value::as_bytes)
+                            .filter(|l| l.len() == layer.len() * 32)
+                            .with_context(|| {
+                                format!("The piece layer of {} is missing", e.path.display())
+                            })?;
+                        Ok(layer
+                            .iter()
+                            .zip(expected.chunks(32))
+                            .enumerate()
+                            .filter(|(_, (node, expected))| node[..] != expected[..])
+                            .map(|(i, _)| piece_range(i, piece_length, e.length))
+                            .collect())
+                    })
+                    .collect::<Result<Vec<Vec<_>>>>()?
+            }
+        };
+
+        let algorithm = self.version.algorithm();
+        let mut filter = filter.clone();
+        let mut results = Vec::with_capacity(entries.len());
+        for (entry, corrupted) in entries.iter().zip(corrupted) {
+            if entry.padding || !filter.is_match(&entry.path) {
+                continue;
+            }
+
+            let success = match fs::metadata(&entry.path) {
+                Ok(metadata) => Some(metadata.len() == entry.length && corrupted.is_empty()),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+                Err(error) => {
+                    return Err(error).with_context(|| {
+                        format!("Failed to get the metadata of {}", entry.path.display())
+                    })
+                }
+            };
+            results.push(Verify {
+                algorithm,
+                file: entry.path.clone(),
+                success,
+                corrupted: if success.is_some() {
+                    merge_ranges(corrupted)
+                } else {
+                    Vec::new()
+                },
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Get the files in the torrent, which are stored in the directory.
+    fn entries(&self, base: &Path) -> Result<Vec<Entry>> {
+        let info = self
+            .metainfo
+            .get("info")
+            .expect("The info dictionary should exist");
+        let name = info
+            .get("name")
+            .and_then(Value::as_bytes)
+            .context("The name of the torrent is missing")?;
+        let dir = base.join(to_path(&[name.to_vec()])?);
+
+        match self.version {
+            #[cfg(feature = "sha-1")]
+            Version::V1 => {
+                let files = match info.get("files") {
+                    Some(files) => files.as_list().context("Invalid list of the files")?,
+                    None => {
+                        let length = info
+                            .get("length")
+                            .and_then(Value::as_integer)
+                            .and_then(|l| u64::try_from(l).ok())
+                            .context("Invalid length of the file")?;
+
+                        return Ok(vec![Entry {
+                            path: dir,
+                            components: Vec::new(),
+                            length,
+                            padding: false,
+                            root: None,
+                        }]);
+                    }
+                };
+
+                files
+                    .iter()
+                    .map(|file| {
+                        let length = file
+                            .get("length")
+                            .and_then(Value::as_integer)
+                            .and_then(|l| u64::try_from(l).ok())
+                            .context("Invalid length of the file")?;
+                        let components = file
+                            .get("path")
+                            .and_then(Value::as_list)
+                            .context("Invalid path of the file")?
+                            .iter()
+                            .map(|c| c.as_bytes().map(<[u8]>::to_vec))
+                            .collect::<Option<Vec<_>>>()
+                            .context("Invalid path of the file")?;
+                        let padding = file
+                            .get("attr")
+                            .and_then(Value::as_bytes)
+                            .map_or(false, |a| a.contains(&b'p'));
+
+                        Ok(Entry {
+                            path: dir.join(to_path(&components)?),
+                            components,
+                            length,
+                            padding,
+                            root: None,
+                        })
+                    })
+                    .collect()
+            }
+            Version::V2 => {
+                let file_tree = info
+                    .get("file tree")
+                    .and_then(Value::as_dict)
+                    .context("Invalid file tree")?;
+
+                let mut entries = Vec::new();
+                collect_files(file_tree, &mut Vec::new(), &mut entries)?;
+                // A single-file torrent is not stored in a directory.
+                let is_single =
+                    file_tree.len() == 1 && entries.len() == 1 && entries[0].components.len() == 1;
+                for entry in &mut entries {
+                    entry.path = if is_single {
+                        base.join(to_path(&entry.components)?)
+                    } else {
+                        dir.join(to_path(&entry.components)?)
+                    };
+                }
+
+                Ok(entries)
+            }
+        }
+    }
+}
+
+impl Checksum {
+    /// Compute the info-hashes of the torrents of the file or directory.
+    ///
+    /// The info-hash of BitTorrent v1 is computed with SHA-1, and that of
+    /// BitTorrent v2 is computed with SHA-256. The files in a directory not
+    /// selected by the filter are skipped. Returns the checksums and the
+    /// total size of the files.
+    pub fn info_hash(
+        algorithms: &[Algorithm],
+        params: &Params,
+        path: impl AsRef<Path>,
+        filter: &Filter,
+    ) -> Result<(Vec<Self>, u64)> {
+        let path = path.as_ref();
+        let (torrents, length) = Torrent::new(
+            path,
+            algorithms,
+            params.piece_length.unwrap_or(DEFAULT_PIECE_LENGTH),
+            filter,
+        )?;
+
+        Ok((Self::from_torrents(path, &torrents), length))
+    }
+
+    /// Get the checksums of the info-hashes of the torrents of the file or
+    /// directory.
+    pub fn from_torrents(path: impl AsRef<Path>, torrents: &[Torrent]) -> Vec<Self> {
+        torrents
+            .iter()
+            .map(|torrent| Self {
+                algorithm: Some(torrent.version.algorithm()),
+                file: path.as_ref().to_path_buf(),
+                digest: torrent.info_hash(),
+                size: None,
+                alternatives: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+/// Get the name of the torrent of the file or directory.
+fn name(path: &Path) -> Result<Vec<u8>> {
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("Failed to get the absolute path of {}", path.display()))?;
+    let name = path
+        .file_name()
+        .with_context(|| format!("Unable to determine the name of {}", path.display()))?;
+
+    walk::to_bytes(name)
+}
+
+/// Collect the files to create a torrent of the file or directory.
+fn local_entries(path: &Path, filter: &Filter) -> Result<Vec<Entry>> {
+    let files = if path.is_dir() {
+        walk::walk(path, &mut filter.clone())?
+    } else {
+        vec![path.to_path_buf()]
+    };
+    ensure!(
+        !files.is_empty(),
+        "No files to create a torrent of {}",
+        path.display()
+    );
+
+    files
+        .into_iter()
+        .map(|file| {
+            let length = fs::metadata(&file)
+                .with_context(|| format!("Failed to get the metadata of {}", file.display()))?
+                .len();
+            let components = file
+                .strip_prefix(path)
+                .expect("File should be in the directory")
+                .iter()
+                .map(walk::to_bytes)
+                .collect::<Result<_>>()?;
+
+            Ok(Entry {
+                path: file,
+                components,
+                length,
+                padding: false,
+                root: None,
+            })
+        })
+        .collect()
+}
+
+/// Collect the files in the file tree of BitTorrent v2.
+fn collect_files(
+    tree: &BTreeMap<Vec<u8>, Value>,
+    components: &mut Vec<Vec<u8>>,
+    entries: &mut Vec<Entry>,
+) -> Result<()> {
+    for (name, node) in tree {
+        let node = node.as_dict().context("Invalid file tree")?;
+        components.push(name.clone());
+
+        if let Some(file) = node.get(&b""[..]) {
+            let length = file
+                .get("length")
+                .and_then(Value::as_integer)
+                .and_then(|l| u64::try_from(l).ok())
+                .context("Invalid length of the file")?;
+            let root = file
+                .get("pieces root")
+                .and_then(Value::as_bytes)
+                .map(<[u8]>::to_vec);
+            ensure!(
+                root.is_some() || length == 0,
+                "The pieces root of the file is missing"
+            );
+
+            entries.push(Entry {
+                path: PathBuf::new(),
+                components: components.clone(),
+                length,
+                padding: false,
+                root,
+            });
+        } else {
+            collect_files(node, components, entries)?;
+        }
+
+        components.pop();
+    }
+
+    Ok(())
+}
+
+/// Insert the file into the file tree of BitTorrent v2.
+fn insert_file(tree: &mut BTreeMap<Vec<u8>, Value>, components: &[Vec<u8>], file: Value) {
+    match components {
+        [name] => {
+            tree.insert(name.clone(), [("", file)].into_iter().collect());
+        }
+        [name, rest @ ..] => {
+            let node = tree
+                .entry(name.clone())
+                .or_insert_with(|| Value::Dict(BTreeMap::new()));
+            if let Value::Dict(ref mut node) = node {
+                insert_file(node, rest, file);
+            }
+        }
+        [] => unreachable!(),
+    }
+}
+
+/// Convert the components of a path in a torrent to a relative path.
+///
+/// Returns an error if any component could escape from the directory.
+fn to_path(components: &[Vec<u8>]) -> Result<PathBuf> {
+    components
+        .iter()
+        .map(|c| {
+            ensure!(
+                !c.is_empty()
+                    && c != b"."
+                    && c != b".."
+                    && !c.iter().any(|b| [b'/', b'\\', b'\0'].contains(b)),
+                "Unsafe path in the torrent: {}",
+                String::from_utf8_lossy(c)
+            );
+
+            walk::from_bytes(c)
+        })
+        .collect()
+}
+
+/// The SHA-1 hashes of the pieces of BitTorrent v1, which are computed
+/// incrementally from the data of the files.
+#[cfg(feature = "sha-1")]
+struct Pieces {
+    piece_length: u64,
+    hasher: Sha1,
+    /// The number of bytes in the current piece.
+    len: u64,
+    pieces: Vec<Vec<u8>>,
+}
+
+#[cfg(feature = "sha-1")]
+impl Pieces {
+    fn new(piece_length: u64) -> Self {
+        Self {
+            piece_length,
+            hasher: Sha1::new(),
+            len: u64::default(),
+            pieces: Vec::new(),
+        }
+    }
+
+    /// Process the data, which may span the pieces.
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = usize::try_from(self.piece_length - self.len)
+                .map_or(data.len(), |r| r.min(data.len()));
+            self.hasher.update(&data[..n]);
+            self.len += u64::try_from(n).expect("Buffer size exceeds the limit");
+            data = &data[n..];
+
+            if self.len == self.piece_length {
+                self.pieces.push(self.hasher.finalize_reset().to_vec());
+                self.len = u64::default();
+            }
+        }
+    }
+
+    /// Retrieve the hashes of the pieces, including the last short piece.
+    fn finalize(mut self) -> Vec<Vec<u8>> {
+        if self.len > 0 {
+            self.pieces.push(self.hasher.finalize().to_vec());
+        }
+
+        self.pieces
+    }
+}
+
+/// Compute the SHA-1 hashes of the pieces of the data.
+#[cfg(feature = "sha-1")]
+fn v1_pieces(mut reader: impl Read, piece_length: u64) -> io::Result<Vec<Vec<u8>>> {
+    let mut pieces = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let n = reader.by_ref().take(piece_length).read_to_end(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        pieces.push(Sha1::digest(&buf).to_vec());
+    }
+
+    Ok(pieces)
+}
+
+/// Compute the SHA-256 hashes of the blocks of the data, which are the leaves
+/// of the Merkle tree.
+fn v2_leaves(mut reader: impl Read) -> io::Result<Vec<Node>> {
+    let mut leaves = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let n = reader.by_ref().take(BLOCK_SIZE).read_to_end(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        leaves.push(Sha256::digest(&buf).into());
+    }
+
+    Ok(leaves)
+}
+
+/// Compute the root of the Merkle tree and the piece layer from the leaves.
+///
+/// The piece layer is empty if the file is not larger than a piece.
+fn v2_tree(leaves: &[Node], piece_length: u64) -> (Node, Vec<Node>) {
+    let blocks_per_piece =
+        usize::try_from(piece_length / BLOCK_SIZE).expect("Piece length exceeds the limit");
+    if leaves.len() <= blocks_per_piece {
+        return (
+            merkle_root(leaves, leaves.len().next_power_of_two(), Node::default()),
+            Vec::new(),
+        );
+    }
+
+    // The last piece is padded with the leaves of zeros.
+    let layer: Vec<_> = leaves
+        .chunks(blocks_per_piece)
+        .map(|l| merkle_root(l, blocks_per_piece, Node::default()))
+        .collect();
+    let pad = merkle_root(&[], blocks_per_piece, Node::default());
+
+    (
+        merkle_root(&layer, layer.len().next_power_of_two(), pad),
+        layer,
+    )
+}
+
+/// Compute the root of the Merkle tree of the nodes, which is padded with
+/// `pad` to `width` nodes.
+fn merkle_root(nodes: &[Node], mut width: usize, mut pad: Node) -> Node {
+    let hash = |left: &Node, right: &Node| -> Node {
+        Sha256::new()
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+            .into()
+    };
+
+    let mut layer = nodes.to_vec();
+    while width > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|n| hash(&n[0], n.get(1).unwrap_or(&pad)))
+            .collect();
+        pad = hash(&pad, &pad);
+        width /= 2;
+    }
+
+    layer.first().copied().unwrap_or(pad)
+}
+
+/// Get the byte range of the piece, which is truncated to `length`.
+fn piece_range(index: usize, piece_length: u64, length: u64) -> Range<u64> {
+    let start = u64::try_from(index).expect("Index exceeds the limit") * piece_length;
+
+    start..(start + piece_length).min(length)
+}
+
+/// Merge the adjacent byte ranges.
+fn merge_ranges(ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+/// Convert the number to an integer of bencode.
+fn to_integer(n: u64) -> Value {
+    Value::from(i64::try_from(n).expect("Number exceeds the limit"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create the files in `data` of the directory.
+    fn create_files(dir: &Path) -> PathBuf {
+        let path = dir.join("data");
+        fs::create_dir_all(path.join("sub")).unwrap();
+        let data: Vec<_> = (0..40000_u32).map(|i| (i * 7 % 251) as u8).collect();
+        fs::write(path.join("a.bin"), data).unwrap();
+        fs::write(path.join("sub/b.txt"), "hello\n").unwrap();
+
+        path
+    }
+
+    #[test]
+    fn info_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = create_files(dir.path());
+        let filter = Filter::new(&[], &[], false).unwrap();
+        let params = Params {
+            piece_length: Some(16384),
+            ..Params::default()
+        };
+
+        #[cfg_attr(not(feature = "sha-1"), allow(unused_mut))]
+        let mut algorithms = vec![Algorithm::torrent(HashAlgorithm::Sha256).unwrap()];
+        #[cfg(feature = "sha-1")]
+        algorithms.push(Algorithm::torrent(HashAlgorithm::Sha1).unwrap());
+        let (checksums, length) =
+            Checksum::info_hash(&algorithms, &params, &path, &filter).unwrap();
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "3288ba3b97c942f51e02bb39b73a5aeaadc6a971f5ec1a983a66985af0ca63ff"
+        );
+        #[cfg(feature = "sha-1")]
+        assert_eq!(
+            hex::encode(&checksums[1].digest),
+            "852641edca460fed67deaef62e4db47bd4316646"
+        );
+        assert_eq!(length, 40006);
+
+        let (checksums, _) =
+            Checksum::info_hash(&algorithms, &params, path.join("sub/b.txt"), &filter).unwrap();
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "cbc7ef2f1f84082338cdf927a4a945463afa4c01dba71cea47525c3585c7aff9"
+        );
+        #[cfg(feature = "sha-1")]
+        assert_eq!(
+            hex::encode(&checksums[1].digest),
+            "fbf373dc4890a655843d390baee25475cdca0c21"
+        );
+
+        assert!(Checksum::info_hash(&algorithms, &Params::default(), "-", &filter).is_err());
+    }
+
+    #[test]
+    fn verify_torrent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = create_files(dir.path());
+        let filter = Filter::new(&[], &[], false).unwrap();
+        let algorithm = Algorithm::torrent(HashAlgorithm::Sha256).unwrap();
+        let (torrents, _) = Torrent::new(&path, &[algorithm], 16384, &filter).unwrap();
+        let torrent = Torrent::from_bytes(&torrents[0].to_bytes()).unwrap();
+
+        let results = torrent.verify(dir.path(), &filter).unwrap();
+        assert!(results.iter().all(|r| r.success == Some(true)));
+
+        let mut data = fs::read(path.join("a.bin")).unwrap();
+        data[20000] ^= 0xff;
+        fs::write(path.join("a.bin"), data).unwrap();
+        fs::remove_file(path.join("sub/b.txt")).unwrap();
+
+        let results = torrent.verify(dir.path(), &filter).unwrap();
+        assert_eq!(results[0].file, path.join("a.bin"));
+        assert_eq!(results[0].success, Some(false));
+        assert_eq!(results[0].corrupted.len(), 1);
+        assert_eq!(results[0].corrupted[0], 16384..32768);
+        assert_eq!(results[1].success, None);
+    }
+
+    #[test]
+    fn piece_layer() {
+        let leaves = vec![[0x01; 32]; 5];
+        let (root, layer) = v2_tree(&leaves, BLOCK_SIZE * 2);
+
+        assert_eq!(layer.len(), 3);
+        assert_eq!(layer[2], merkle_root(&[[0x01; 32]], 2, Node::default()));
+        assert_eq!(root, merkle_root(&leaves, 8, Node::default()));
+        assert!(Torrent::from_bytes(b"d4:infod4:name3:fooee").is_err());
+    }
+}
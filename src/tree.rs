@@ -0,0 +1,271 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::fs::{self, File};
+use std::path::Path;
+
+use anyhow::{ensure, Context, Result};
+
+use crate::digest::Hasher;
+use crate::value::{Algorithm, Checksum, Params};
+use crate::walk::{self, Filter};
+
+/// The tag which is hashed before the entries of a directory, so the tree
+/// digest does not collide with the message digest of a file.
+const TREE_TAG: &[u8] = b"rshash-tree-v1\0";
+
+/// The type of an entry in a directory.
+#[derive(Clone, Copy)]
+enum Kind {
+    Directory,
+    File,
+    Executable,
+    Symlink,
+}
+
+impl Kind {
+    /// Get the tag which represents the type in the tree digest.
+    ///
+    /// An executable file is tagged as a regular file unless the executable
+    /// bit is included.
+    const fn tag(self, executable: bool) -> u8 {
+        match self {
+            Self::Directory => b'd',
+            Self::Executable if executable => b'x',
+            Self::File | Self::Executable => b'f',
+            Self::Symlink => b'l',
+        }
+    }
+}
+
+/// An entry in a directory.
+struct Entry {
+    kind: Kind,
+    name: Vec<u8>,
+    digests: Vec<Vec<u8>>,
+}
+
+impl Checksum {
+    /// Compute the tree digests of the directory.
+    ///
+    /// The tree digest of a directory is the message digest of the tag and its
+    /// entries sorted by name, and each entry consists of the type, the name
+    /// and the message digest of the file, the target of the symbolic link or
+    /// the tree digest of the subdirectory. The files and directories not
+    /// selected by the filter are skipped. Returns the checksums and the total
+    /// size of the files.
+    ///
+    /// Returns an error if the path is not a directory.
+    pub fn tree_digest(
+        algorithms: &[Algorithm],
+        params: &Params,
+        path: impl AsRef<Path>,
+        filter: &Filter,
+    ) -> Result<(Vec<Self>, u64)> {
+        let path = path.as_ref();
+        ensure!(path.is_dir(), "{} is not a directory", path.display());
+
+        let (digests, length) = dir_digests(algorithms, params, path, &mut filter.clone())?;
+        let checksums = algorithms
+            .iter()
+            .zip(digests)
+            .map(|(algorithm, digest)| Self {
+                algorithm: Some(*algorithm),
+                file: path.to_path_buf(),
+                digest,
+                size: None,
+                alternatives: Vec::new(),
+            })
+            .collect();
+
+        Ok((checksums, length))
+    }
+}
+
+/// Compute the message digests of the file.
+fn file_digests(
+    algorithms: &[Algorithm],
+    params: &Params,
+    path: &Path,
+) -> Result<(Vec<Vec<u8>>, u64)> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let (checksums, length) = Checksum::digest_reader(algorithms, params, path, file)?;
+
+    Ok((checksums.into_iter().map(|c| c.digest).collect(), length))
+}
+
+/// Compute the tree digests of the directory.
+fn dir_digests(
+    algorithms: &[Algorithm],
+    params: &Params,
+    dir: &Path,
+    filter: &mut Filter,
+) -> Result<(Vec<Vec<u8>>, u64)> {
+    let mut entries = Vec::new();
+    let mut length = u64::default();
+
+    let read_dir = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read the directory {}", dir.display()))?;
+    for entry in read_dir {
+        let entry =
+            entry.with_context(|| format!("Failed to read the directory {}", dir.display()))?;
+        let path = entry.path();
+        let file_type = entry
+            .file_type()
+            .with_context(|| format!("Failed to get the file type of {}", path.display()))?;
+
+        let (kind, digests) = if file_type.is_dir() {
+            if filter.is_ignored(&path, true) {
+                continue;
+            }
+
+            let (digests, size) = dir_digests(algorithms, params, &path, filter)?;
+            length += size;
+
+            (Kind::Directory, digests)
+        } else if file_type.is_file() {
+            if !filter.is_match(&path) {
+                continue;
+            }
+
+            let metadata = entry
+                .metadata()
+                .with_context(|| format!("Failed to get the metadata of {}", path.display()))?;
+            let (digests, size) = file_digests(algorithms, params, &path)?;
+            length += size;
+
+            if walk::is_executable(&metadata) {
+                (Kind::Executable, digests)
+            } else {
+                (Kind::File, digests)
+            }
+        } else if file_type.is_symlink() {
+            if !filter.is_match(&path) {
+                continue;
+            }
+
+            let target = fs::read_link(&path)
+                .with_context(|| format!("Failed to read the symbolic link {}", path.display()))?;
+            let target = walk::to_bytes(target.as_os_str())?;
+            let (checksums, _) =
+                Checksum::digest_reader(algorithms, params, &path, target.as_slice())?;
+
+            (
+                Kind::Symlink,
+                checksums.into_iter().map(|c| c.digest).collect(),
+            )
+        } else {
+            continue;
+        };
+
+        entries.push(Entry {
+            kind,
+            name: walk::to_bytes(&entry.file_name())?,
+            digests,
+        });
+    }
+    entries.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let hashers: Result<Vec<_>> = algorithms.iter().map(|a| a.hasher(params)).collect();
+    let mut hashers = hashers?;
+    for hasher in &mut hashers {
+        hasher.update(TREE_TAG);
+    }
+    for entry in entries {
+        for ((algorithm, hasher), digest) in algorithms.iter().zip(&mut hashers).zip(entry.digests)
+        {
+            hasher.update(&[entry.kind.tag(algorithm.executable), b' ']);
+            hasher.update(&entry.name);
+            hasher.update(b"\0");
+            hasher.update(&digest);
+        }
+    }
+
+    Ok((hashers.into_iter().map(Hasher::finalize).collect(), length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::HashAlgorithm;
+    use crate::verify::Verify;
+
+    #[test]
+    #[cfg(unix)]
+    fn tree_digest() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path();
+        fs::write(path.join("foo"), "foo\n").unwrap();
+        fs::set_permissions(path.join("foo"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::write(path.join("bar.txt"), "bar\n").unwrap();
+        fs::create_dir(path.join("bar")).unwrap();
+        fs::write(path.join("bar/baz.txt"), "baz\n").unwrap();
+        fs::create_dir(path.join("empty")).unwrap();
+        symlink("foo", path.join("link")).unwrap();
+
+        let algorithm = Algorithm::from(HashAlgorithm::Sha256).into_tree(false);
+        let algorithms = [algorithm, algorithm.into_tree(true)];
+        let filter = Filter::new(&[], &[], false).unwrap();
+        let (checksums, length) =
+            Checksum::tree_digest(&algorithms, &Params::default(), path, &filter).unwrap();
+
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "d24e1d0bd57db60906d22d4260040041259fdfbc1696536d6d3037bfe90998a5"
+        );
+        assert_eq!(
+            hex::encode(&checksums[1].digest),
+            "b3ca5bfb6bc2f213bdcc4d4e4720c6726e7ea6044c0c7acc7aff93909b611568"
+        );
+        assert_eq!(length, 12);
+
+        let filter = Filter::new(&[], &["empty".to_string()], false).unwrap();
+        let (checksums, _) =
+            Checksum::tree_digest(&algorithms, &Params::default(), path, &filter).unwrap();
+        assert_eq!(
+            hex::encode(&checksums[0].digest),
+            "f4f241a0e6c471ff3e6bbd329baf86891d294b218ba84f8bcc36b6672b172a1b"
+        );
+    }
+
+    #[test]
+    fn file_tree_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let algorithm = Algorithm::from(HashAlgorithm::Sha256).into_tree(false);
+        let filter = Filter::new(&[], &[], false).unwrap();
+        let (checksums, _) =
+            Checksum::tree_digest(&[algorithm], &Params::default(), dir.path(), &filter).unwrap();
+
+        // The entries of the directory are hashed after the tag.
+        let mut entries = b"f a.txt\0".to_vec();
+        entries.extend(Checksum::digest(HashAlgorithm::Sha256, &("-", "a")).digest);
+        let file = dir.path().join("entries");
+        fs::write(&file, &entries).unwrap();
+        assert_ne!(
+            Checksum::digest(HashAlgorithm::Sha256, &("-", &entries)).digest,
+            checksums[0].digest
+        );
+        assert_eq!(
+            Checksum::digest(HashAlgorithm::Sha256, &("-", [TREE_TAG, &entries].concat())).digest,
+            checksums[0].digest
+        );
+
+        assert!(Checksum::tree_digest(&[algorithm], &Params::default(), &file, &filter).is_err());
+        let result = Verify::check(
+            &Checksum {
+                file,
+                ..checksums.into_iter().next().unwrap()
+            },
+            &Params::default(),
+            &filter,
+        )
+        .unwrap();
+        assert_eq!(result.success, Some(false));
+    }
+}
@@ -0,0 +1,233 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use data_encoding::BASE32_NOPAD;
+use digest::{Digest, Output};
+use serde::Serialize;
+use tiger::Tiger;
+
+use crate::digest::Hasher;
+
+/// The size of a leaf of the Tiger Tree Hash.
+const LEAF_SIZE: usize = 1024;
+
+/// A hasher which computes the Tiger Tree Hash (TTH).
+///
+/// The input is split into 1024-byte leaves, and the Merkle tree is built as
+/// defined by THEX. Only the roots of the complete subtrees are kept, so the
+/// memory usage is logarithmic in the size of the input unless the leaves are
+/// recorded.
+#[derive(Default)]
+pub struct Tth {
+    leaf: Vec<u8>,
+    count: u64,
+    stack: Vec<(u32, Output<Tiger>)>,
+    leaves: Option<Vec<Output<Tiger>>>,
+}
+
+impl Tth {
+    /// Create a new hasher which also records the hashes of the leaves.
+    pub fn with_leaves() -> Self {
+        Self {
+            leaves: Some(Vec::new()),
+            ..Self::default()
+        }
+    }
+
+    /// Hash the buffered leaf and merge the complete subtrees.
+    fn push_leaf(&mut self) {
+        let mut hash = Tiger::new()
+            .chain_update([0x00])
+            .chain_update(&self.leaf)
+            .finalize();
+        self.leaf.clear();
+        self.count += 1;
+        if let Some(ref mut leaves) = self.leaves {
+            leaves.push(hash);
+        }
+
+        let mut level = 0;
+        while let Some(&(top, _)) = self.stack.last() {
+            if top != level {
+                break;
+            }
+
+            let (_, left) = self.stack.pop().expect("Stack should not be empty");
+            hash = node(&left, &hash);
+            level += 1;
+        }
+        self.stack.push((level, hash));
+    }
+
+    /// Flush the last leaf, which is empty if the input is empty.
+    fn flush_leaf(&mut self) {
+        if !self.leaf.is_empty() || self.count == 0 {
+            self.push_leaf();
+        }
+    }
+
+    /// Get the root hash of the tree.
+    fn root(&mut self) -> Output<Tiger> {
+        self.flush_leaf();
+
+        let (_, mut root) = self.stack.pop().expect("Stack should not be empty");
+        while let Some((_, left)) = self.stack.pop() {
+            root = node(&left, &root);
+        }
+
+        root
+    }
+
+    /// Get all the levels of the tree from the root to the leaves.
+    ///
+    /// Returns `None` if the leaves are not recorded.
+    pub fn levels(mut self) -> Option<Vec<Vec<Output<Tiger>>>> {
+        self.flush_leaf();
+
+        let mut levels = vec![self.leaves?];
+        while levels[0].len() > 1 {
+            // The last node without a sibling is promoted to the next level.
+            let level = levels[0]
+                .chunks(2)
+                .map(|nodes| match nodes {
+                    [left, right] => node(left, right),
+                    [node] => *node,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.insert(0, level);
+        }
+
+        Some(levels)
+    }
+}
+
+impl Hasher for Tth {
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let len = (LEAF_SIZE - self.leaf.len()).min(data.len());
+            self.leaf.extend_from_slice(&data[..len]);
+            data = &data[len..];
+
+            if self.leaf.len() == LEAF_SIZE {
+                self.push_leaf();
+            }
+        }
+    }
+
+    fn finalize(mut self: Box<Self>) -> Vec<u8> {
+        self.root().to_vec()
+    }
+}
+
+/// Compute the hash of an internal node from its children.
+fn node(left: &Output<Tiger>, right: &Output<Tiger>) -> Output<Tiger> {
+    Tiger::new()
+        .chain_update([0x01])
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+}
+
+/// A reader which also passes the data read to the hasher of TTH, so the
+/// Merkle tree is built while the other message digests are computed.
+pub struct TreeReader<'a, R> {
+    reader: R,
+    hasher: &'a mut Tth,
+}
+
+impl<'a, R: Read> TreeReader<'a, R> {
+    /// Create a new reader which passes the data to the hasher.
+    pub fn new(reader: R, hasher: &'a mut Tth) -> Self {
+        Self { reader, hasher }
+    }
+}
+
+impl<R: Read> Read for TreeReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        Hasher::update(self.hasher, &buf[..n]);
+
+        Ok(n)
+    }
+}
+
+/// The full Merkle tree of the Tiger Tree Hash of a file.
+#[derive(Serialize)]
+pub struct TthTree {
+    pub file: PathBuf,
+    /// The hashes of each level from the root to the leaves in base32.
+    pub levels: Vec<Vec<String>>,
+}
+
+impl TthTree {
+    /// Build the tree from the hasher which recorded the leaves.
+    ///
+    /// Returns the root hash and the tree.
+    pub fn new(file: impl Into<PathBuf>, hasher: Tth) -> (Vec<u8>, Self) {
+        let levels = hasher.levels().expect("Leaves should be recorded");
+        let root = levels[0][0].to_vec();
+        let levels = levels
+            .into_iter()
+            .map(|level| level.iter().map(|n| BASE32_NOPAD.encode(n)).collect())
+            .collect();
+
+        (
+            root,
+            Self {
+                file: file.into(),
+                levels,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tth(data: &[u8]) -> String {
+        let mut hasher = Box::new(Tth::default());
+        Hasher::update(hasher.as_mut(), data);
+
+        BASE32_NOPAD.encode(&hasher.finalize())
+    }
+
+    #[test]
+    fn verify_tth() {
+        assert_eq!(tth(b""), "LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ");
+        assert_eq!(tth(&[0x00]), "VK54ZIEEVTWNAUI5D5RDFIL37LX2IQNSTAXFKSA");
+        assert_eq!(
+            tth(&[b'A'; 1024]),
+            "L66Q4YVNAFWVS23X2HJIRA5ZJ7WXR3F26RSASFA"
+        );
+        assert_eq!(
+            tth(&[b'A'; 1025]),
+            "PZMRYHGY6LTBEH63ZWAHDORHSYTLO4LEFUIKHWY"
+        );
+    }
+
+    #[test]
+    fn tth_tree() {
+        let data = vec![b'A'; LEAF_SIZE * 4 + 1];
+        let mut hasher = Tth::with_leaves();
+        let mut reader = TreeReader::new(data.as_slice(), &mut hasher);
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        let (root, tree) = TthTree::new("-", hasher);
+
+        assert_eq!(
+            tree.levels.iter().map(Vec::len).collect::<Vec<_>>(),
+            [1, 2, 3, 5]
+        );
+        assert_eq!(tree.levels[0][0], tth(&data));
+        assert_eq!(BASE32_NOPAD.encode(&root), tth(&data));
+        assert_eq!(tree.levels[1][1], tree.levels[2][2]);
+        assert_eq!(tree.levels[2][2], tree.levels[3][4]);
+    }
+}
@@ -4,76 +4,555 @@
 // Copyright (C) 2021 Shun Sakai
 //
 
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
-use std::str::FromStr;
+use std::str::{self, FromStr};
 
-use anyhow::{anyhow, Error, Result};
+use anyhow::{anyhow, bail, ensure, Context, Error, Result};
 use clap::ArgEnum;
-use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DeserializeFromStr, SerializeDisplay};
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+use serde_with::{serde_as, DeserializeAs, DeserializeFromStr, SerializeDisplay};
 use strum::{Display, EnumString, EnumVariantNames};
 
-use crate::regex;
+use crate::{bytes_regex, encoding, escape, multihash, regex, walk};
 
 #[serde_as]
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize)]
 pub struct Checksum {
-    pub algorithm: Option<HashAlgorithm>,
+    pub algorithm: Option<Algorithm>,
     pub file: PathBuf,
-    #[serde_as(as = "serde_with::hex::Hex")]
+    #[serde_as(as = "EncodedDigest")]
     pub digest: Vec<u8>,
+    /// The size of the file in bytes, if it is recorded with the message
+    /// digest.
+    #[serde(skip)]
+    pub size: Option<u64>,
+    /// The other message digests which are also accepted when verifying.
+    #[serde(skip)]
+    pub alternatives: Vec<Vec<u8>>,
 }
 
-impl FromStr for Checksum {
-    type Err = Error;
+/// The index of an improperly formatted line of a checksum file and the
+/// error.
+pub type ImproperLine = (usize, Error);
 
-    fn from_str(checksum: &str) -> Result<Self> {
+/// The parameters for computing message digests.
+#[derive(Default)]
+pub struct Params {
+    /// The secret key for keyed hashing.
+    pub key: Option<Vec<u8>>,
+    /// The customization string for cSHAKE and KangarooTwelve.
+    pub customization: Option<Vec<u8>>,
+    /// The length of a piece of a torrent.
+    pub piece_length: Option<u64>,
+}
+
+/// The checksums of a file for multiple hash algorithms.
+#[serde_as]
+#[derive(Deserialize)]
+pub struct MultiChecksum {
+    pub file: PathBuf,
+    #[serde_as(as = "BTreeMap<_, EncodedDigest>")]
+    pub digests: BTreeMap<Algorithm, Vec<u8>>,
+}
+
+impl From<MultiChecksum> for Vec<Checksum> {
+    fn from(checksums: MultiChecksum) -> Self {
+        checksums
+            .digests
+            .into_iter()
+            .map(|(algorithm, digest)| Checksum {
+                algorithm: Some(algorithm),
+                file: checksums.file.clone(),
+                digest,
+                size: None,
+                alternatives: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+/// The message digest in JSON, which is encoded in any of the supported
+/// encodings.
+struct EncodedDigest;
+
+impl<'de> DeserializeAs<'de, Vec<u8>> for EncodedDigest {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let digest = String::deserialize(deserializer)?;
+
+        multihash::decode(&digest)
+            .map(|(_, digest)| digest)
+            .or_else(|| encoding::decode_any(&digest, None).into_iter().next())
+            .ok_or_else(|| de::Error::custom("Invalid message digest"))
+    }
+}
+
+impl Checksum {
+    /// Parse a checksum line.
+    ///
+    /// The line is parsed as bytes, so the file name does not have to be valid
+    /// UTF-8 on Unix. The message digest is decoded with the encoding if it is
+    /// specified. Otherwise, the encoding is detected, and the algorithm is
+    /// used to select the encoding which results in the length of its message
+    /// digest.
+    pub fn parse(
+        checksum: &[u8],
+        algorithm: Option<Algorithm>,
+        encoding: Option<Encoding>,
+    ) -> Result<Self> {
+        #[cfg(feature = "md4")]
+        if let Some(checksum) = str::from_utf8(checksum)
+            .ok()
+            .and_then(Self::parse_ed2k_link)
+        {
+            return Ok(checksum);
+        }
         if let Some(captures) =
-            regex!(r"^(?P<digest>[[:xdigit:]]{32,128})  (?P<file>.*)$").captures(checksum)
+            bytes_regex!(r"(?-u)^(?P<integrity>sha(?:256|384|512)-[^ ]+(?: [^ ]+)*)  (?P<file>.*)$")
+                .captures(checksum)
         {
-            // Parse as SFV-style checksum.
-            return Ok(Self {
-                algorithm: None,
-                file: captures["file"].trim().into(),
-                digest: hex::decode(&captures["digest"])
-                    .expect("Failed to decode a hex string into raw bytes"),
-            });
-        }
-        if let Some(captures) = regex!(
-            r"^(?P<algorithm>[[:alnum:]-]+) \((?P<file>.*)\) = (?P<digest>[[:xdigit:]]{32,128})$"
+            // Parse as SRI-style checksum.
+            return Self::parse_integrity(
+                ascii(&captures["integrity"])?,
+                parse_file(trim(&captures["file"]), false)?,
+            )
+            .ok_or_else(|| anyhow!("Improperly formatted checksum line"));
+        }
+
+        if let Some(captures) =
+            bytes_regex!(r"(?-u)^(?P<escaped>\\)?(?P<digest>[[:alnum:]+/=_-]+) [ *](?P<file>.*)$")
+                .captures(checksum)
+        {
+            // Parse as GNU-style checksum. The binary mode marker `*` is
+            // ignored, since the files are always read in binary mode.
+            if let Some((algorithm, mut digests)) =
+                decode_digest(ascii(&captures["digest"])?, algorithm, encoding)
+            {
+                return Ok(Self {
+                    algorithm,
+                    file: parse_file(&captures["file"], captures.name("escaped").is_some())?,
+                    digest: digests.remove(0),
+                    size: None,
+                    alternatives: digests,
+                });
+            }
+        }
+        if let Some(captures) = bytes_regex!(
+            r"(?-u)^(?P<escaped>\\)?(?P<algorithm>[[:alnum:]/-]+) \((?P<file>.*)\) = (?P<digest>[[:alnum:]+/=_-]+)$"
         )
         .captures(checksum)
         {
             // Parse as BSD-style checksum.
-            return Ok(Self {
-                algorithm: captures["algorithm"].parse().ok(),
-                file: captures["file"].trim().into(),
-                digest: hex::decode(&captures["digest"])
-                    .expect("Failed to decode a hex string into raw bytes"),
-            });
+            let name: Option<Algorithm> = ascii(&captures["algorithm"])?.parse().ok();
+            if let Some((inferred, mut digests)) =
+                decode_digest(ascii(&captures["digest"])?, algorithm.or(name), encoding)
+            {
+                // The hash algorithm must match the multicodec of the multihash.
+                ensure!(
+                    name.zip(inferred)
+                        .map_or(true, |(n, i)| n.hash == i.hash && n.length == i.length),
+                    "Improperly formatted checksum line"
+                );
+
+                return Ok(Self {
+                    algorithm: name.or(inferred),
+                    file: parse_file(&captures["file"], captures.name("escaped").is_some())?,
+                    digest: digests.remove(0),
+                    size: None,
+                    alternatives: digests,
+                });
+            }
+        }
+
+        if let Some(captures) =
+            bytes_regex!(r"(?-u)^(?P<file>.+) (?P<digest>[[:alnum:]+/=_-]{4,})$").captures(checksum)
+        {
+            // Parse as SFV-style checksum, which is always CRC32. The digest is
+            // in hexadecimal unless the encoding is specified.
+            let digest = ascii(&captures["digest"])?;
+            let digest = match encoding {
+                Some(encoding) => encoding.decode(digest),
+                None => regex!(r"^[[:xdigit:]]{8}$")
+                    .is_match(digest)
+                    .then(|| hex::decode(digest).ok())
+                    .flatten(),
+            };
+            if let Some(digest) = digest.filter(|d| d.len() == 4) {
+                return Ok(Self {
+                    algorithm: Some(HashAlgorithm::Crc32.into()),
+                    file: parse_file(trim(&captures["file"]), false)?,
+                    digest,
+                    size: None,
+                    alternatives: Vec::new(),
+                });
+            }
         }
 
         Err(anyhow!("Improperly formatted checksum line"))
     }
 }
 
+impl FromStr for Checksum {
+    type Err = Error;
+
+    fn from_str(checksum: &str) -> Result<Self> {
+        Self::parse(checksum.as_bytes(), None, None)
+    }
+}
+
+/// Convert the bytes matched by an ASCII-only pattern to a string.
+fn ascii(bytes: &[u8]) -> Result<&str> {
+    str::from_utf8(bytes).context("Improperly formatted checksum line")
+}
+
+/// Trim the leading and trailing ASCII whitespace of the bytes.
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+
+    &bytes[start..end]
+}
+
+/// Parse the file name of a checksum line.
+///
+/// The file name is used as is unless the line starts with a backslash, as GNU
+/// coreutils does. On non-Unix platforms, the file name must be valid UTF-8.
+fn parse_file(file: &[u8], escaped: bool) -> Result<PathBuf> {
+    let file = if escaped {
+        escape::unescape(file).ok_or_else(|| anyhow!("Improperly formatted checksum line"))?
+    } else {
+        file.to_vec()
+    };
+
+    walk::from_bytes(&file).map(PathBuf::from)
+}
+
+/// Decode the message digest.
+///
+/// If the encoding is not specified, a multihash or a CID is decoded and the
+/// hash algorithm is inferred from it, or the encoding is detected. The
+/// hexadecimal which results in the length of the message digest of the
+/// algorithm is tried first, since it can also be a valid multibase string.
+/// The other encodings are detected only if the length is known, since a word
+/// of text can also be valid in them. Returns the inferred algorithm and the
+/// decodings of the message digest, which are not empty.
+fn decode_digest(
+    digest: &str,
+    algorithm: Option<Algorithm>,
+    encoding: Option<Encoding>,
+) -> Option<(Option<Algorithm>, Vec<Vec<u8>>)> {
+    if let Some(encoding) = encoding {
+        return encoding.decode(digest).map(|digest| (None, vec![digest]));
+    }
+    let len = algorithm.map(Algorithm::digest_len);
+    if let Some(digest) = Encoding::Hex
+        .decode(digest)
+        .filter(|d| len.map_or(true, |len| d.len() == len))
+    {
+        return Some((None, vec![digest]));
+    }
+    if let Some((algorithm, digest)) = multihash::decode(digest) {
+        return Some((Some(algorithm), vec![digest]));
+    }
+
+    let digests = len
+        .map(|len| encoding::decode_any(digest, Some(len)))
+        .unwrap_or_default();
+
+    (!digests.is_empty()).then(|| (None, digests))
+}
+
+/// The maximum length of the message digest of the extendable-output
+/// functions in bits.
+const MAX_XOF_LENGTH: usize = 65536;
+
+/// The algorithm for computing a message digest.
+#[derive(
+    Clone, Copy, Debug, DeserializeFromStr, Eq, Hash, Ord, PartialEq, PartialOrd, SerializeDisplay,
+)]
+pub struct Algorithm {
+    /// The underlying hash algorithm.
+    pub hash: HashAlgorithm,
+    /// Whether to compute HMAC instead of a plain message digest.
+    pub hmac: bool,
+    /// The length of the message digest in bits, if it differs from the
+    /// default length of the hash algorithm.
+    pub length: Option<usize>,
+    /// Whether to compute the git object ID instead of a plain message
+    /// digest.
+    pub git: bool,
+    /// Whether to compute the info-hash of BitTorrent instead of a plain
+    /// message digest.
+    pub torrent: bool,
+    /// Whether to compute the tree digest of a directory.
+    pub tree: bool,
+    /// Whether to include the executable bit of the files in the tree digest.
+    pub executable: bool,
+}
+
+impl Algorithm {
+    /// Create a new algorithm.
+    ///
+    /// Returns an error if the combination of HMAC and the length is not
+    /// supported by the hash algorithm. The length equal to the default length
+    /// is normalized to `None`.
+    pub fn new(hash: HashAlgorithm, hmac: bool, length: Option<usize>) -> Result<Self> {
+        let length = match length {
+            Some(length) => {
+                let max = match hash {
+                    HashAlgorithm::Blake2b => 512,
+                    HashAlgorithm::Blake2s => 256,
+                    HashAlgorithm::Blake3
+                    | HashAlgorithm::CShake128
+                    | HashAlgorithm::CShake256
+                    | HashAlgorithm::K12
+                    | HashAlgorithm::Shake128
+                    | HashAlgorithm::Shake256 => MAX_XOF_LENGTH,
+                    #[cfg(feature = "ascon-hash")]
+                    HashAlgorithm::AsconXof128 => MAX_XOF_LENGTH,
+                    _ => bail!("{} does not support a variable output length", hash),
+                };
+                ensure!(!hmac, "HMAC does not support a variable output length");
+                ensure!(
+                    length > 0 && length % 8 == 0 && length <= max,
+                    "Invalid length for {}: {}",
+                    hash,
+                    length
+                );
+
+                Some(length).filter(|&l| Some(l) != hash.default_length())
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            hash,
+            hmac,
+            length,
+            ..hash.into()
+        })
+    }
+
+    /// Create a new algorithm which computes the git object ID.
+    ///
+    /// Returns an error if the hash algorithm is not an object format of git,
+    /// which is SHA-1 or SHA-256.
+    pub fn git(hash: HashAlgorithm) -> Result<Self> {
+        match hash {
+            #[cfg(feature = "sha-1")]
+            HashAlgorithm::Sha1 => (),
+            HashAlgorithm::Sha256 => (),
+            _ => bail!("{} is not supported as the object format of git", hash),
+        }
+
+        Ok(Self {
+            git: true,
+            ..hash.into()
+        })
+    }
+
+    /// Create a new algorithm which computes the info-hash of BitTorrent.
+    ///
+    /// SHA-1 is used by BitTorrent v1, and SHA-256 is used by BitTorrent v2.
+    /// Returns an error if the hash algorithm is neither of them.
+    pub fn torrent(hash: HashAlgorithm) -> Result<Self> {
+        match hash {
+            #[cfg(feature = "sha-1")]
+            HashAlgorithm::Sha1 => (),
+            HashAlgorithm::Sha256 => (),
+            _ => bail!("{} is not supported by BitTorrent", hash),
+        }
+
+        Ok(Self {
+            torrent: true,
+            ..hash.into()
+        })
+    }
+
+    /// Convert into the algorithm which computes the tree digest of a
+    /// directory.
+    pub const fn into_tree(self, executable: bool) -> Self {
+        Self {
+            tree: true,
+            executable,
+            ..self
+        }
+    }
+}
+
+impl From<HashAlgorithm> for Algorithm {
+    fn from(hash: HashAlgorithm) -> Self {
+        Self {
+            hash,
+            hmac: false,
+            length: None,
+            git: false,
+            torrent: false,
+            tree: false,
+            executable: false,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.executable {
+            write!(f, "TREE-EXEC-")?;
+        } else if self.tree {
+            write!(f, "TREE-")?;
+        }
+        if self.hmac {
+            write!(f, "HMAC-")?;
+        }
+        if self.git {
+            write!(f, "GIT-")?;
+        }
+        if self.torrent {
+            write!(f, "TORRENT-")?;
+        }
+        write!(f, "{}", self.hash)?;
+        if let Some(length) = self.length {
+            write!(f, "-{}", length)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(algorithm: &str) -> Result<Self> {
+        for (prefix, executable) in [("TREE-EXEC-", true), ("TREE-", false)] {
+            if let Some(name) = algorithm.get(prefix.len()..) {
+                if algorithm[..prefix.len()].eq_ignore_ascii_case(prefix) {
+                    let algorithm: Self = name.parse()?;
+                    ensure!(
+                        !algorithm.git,
+                        "Git object IDs do not support the tree digest"
+                    );
+                    ensure!(
+                        !algorithm.torrent,
+                        "Info-hashes do not support the tree digest"
+                    );
+
+                    return Ok(algorithm.into_tree(executable));
+                }
+            }
+        }
+
+        let (algorithm, hmac) = match algorithm.get(..5) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("HMAC-") => (&algorithm[5..], true),
+            _ => (algorithm, false),
+        };
+        if let Some(prefix) = algorithm.get(..4) {
+            if prefix.eq_ignore_ascii_case("GIT-") {
+                let hash = algorithm[4..]
+                    .parse()
+                    .map_err(|_| anyhow!("Unknown hash algorithm: {}", algorithm))?;
+                ensure!(!hmac, "HMAC does not support git object IDs");
+
+                return Self::git(hash);
+            }
+        }
+        if let Some(prefix) = algorithm.get(..8) {
+            if prefix.eq_ignore_ascii_case("TORRENT-") {
+                let hash = algorithm[8..]
+                    .parse()
+                    .map_err(|_| anyhow!("Unknown hash algorithm: {}", algorithm))?;
+                ensure!(!hmac, "HMAC does not support info-hashes");
+
+                return Self::torrent(hash);
+            }
+        }
+
+        if let Ok(hash) = algorithm.parse() {
+            return Self::new(hash, hmac, None);
+        }
+        // The length of the message digest is appended to the name, such as
+        // `BLAKE2b-256`.
+        let (hash, length) = algorithm
+            .rsplit_once('-')
+            .ok_or_else(|| anyhow!("Unknown hash algorithm: {}", algorithm))?;
+        let hash = hash
+            .parse()
+            .map_err(|_| anyhow!("Unknown hash algorithm: {}", algorithm))?;
+        let length = length
+            .parse()
+            .map_err(|_| anyhow!("Unknown hash algorithm: {}", algorithm))?;
+
+        Self::new(hash, hmac, Some(length))
+    }
+}
+
 #[derive(
     ArgEnum,
     Clone,
     Copy,
+    Debug,
     DeserializeFromStr,
     Display,
     EnumString,
     EnumVariantNames,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
     SerializeDisplay,
 )]
 #[strum(serialize_all = "SCREAMING-KEBAB-CASE", ascii_case_insensitive)]
 pub enum HashAlgorithm {
+    #[strum(to_string = "Adler-32", serialize = "Adler32")]
+    Adler32,
+    #[cfg(feature = "sha-1")]
+    Aich,
+    #[cfg(feature = "ascon-hash")]
+    #[strum(to_string = "Ascon-Hash256")]
+    AsconHash256,
+    #[cfg(feature = "ascon-hash")]
+    #[strum(to_string = "Ascon-XOF128")]
+    AsconXof128,
+    #[cfg(feature = "belt-hash")]
+    #[strum(to_string = "belt-hash")]
+    BeltHash,
+    #[cfg(feature = "blake-hash")]
+    #[strum(serialize = "BLAKE-224")]
+    Blake224,
+    #[cfg(feature = "blake-hash")]
+    #[strum(serialize = "BLAKE-256")]
+    Blake256,
+    #[cfg(feature = "blake-hash")]
+    #[strum(serialize = "BLAKE-384")]
+    Blake384,
+    #[cfg(feature = "blake-hash")]
+    #[strum(serialize = "BLAKE-512")]
+    Blake512,
     #[strum(to_string = "BLAKE2b")]
     Blake2b,
     #[strum(to_string = "BLAKE2s")]
     Blake2s,
     Blake3,
+    Crc32,
+    Crc32c,
+    Crc64Ecma,
+    Crc64Nvme,
+    #[strum(to_string = "cSHAKE128")]
+    #[clap(name = "cshake128")]
+    CShake128,
+    #[strum(to_string = "cSHAKE256")]
+    #[clap(name = "cshake256")]
+    CShake256,
+    #[cfg(feature = "md4")]
+    Ed2k,
     #[strum(serialize = "FSB-160")]
     Fsb160,
     #[strum(serialize = "FSB-224")]
@@ -97,6 +576,20 @@ pub enum HashAlgorithm {
     Groestl384,
     #[strum(serialize = "Groestl-512")]
     Groestl512,
+    #[cfg(feature = "jh")]
+    #[strum(serialize = "JH-224")]
+    Jh224,
+    #[cfg(feature = "jh")]
+    #[strum(serialize = "JH-256")]
+    Jh256,
+    #[cfg(feature = "jh")]
+    #[strum(serialize = "JH-384")]
+    Jh384,
+    #[cfg(feature = "jh")]
+    #[strum(serialize = "JH-512")]
+    Jh512,
+    #[strum(to_string = "K12", serialize = "KangarooTwelve")]
+    K12,
     #[strum(serialize = "Keccak-224")]
     Keccak224,
     #[strum(serialize = "Keccak-256")]
@@ -105,6 +598,18 @@ pub enum HashAlgorithm {
     Keccak384,
     #[strum(serialize = "Keccak-512")]
     Keccak512,
+    #[cfg(feature = "kupyna")]
+    #[strum(serialize = "Kupyna-224")]
+    Kupyna224,
+    #[cfg(feature = "kupyna")]
+    #[strum(serialize = "Kupyna-256")]
+    Kupyna256,
+    #[cfg(feature = "kupyna")]
+    #[strum(serialize = "Kupyna-384")]
+    Kupyna384,
+    #[cfg(feature = "kupyna")]
+    #[strum(serialize = "Kupyna-512")]
+    Kupyna512,
     #[cfg(feature = "md2")]
     Md2,
     #[cfg(feature = "md4")]
@@ -123,6 +628,20 @@ pub enum HashAlgorithm {
     Sha256,
     Sha384,
     Sha512,
+    #[strum(
+        to_string = "SHA-512/224",
+        serialize = "SHA512/224",
+        serialize = "SHA512-224",
+        serialize = "SHA512t224"
+    )]
+    Sha512_224,
+    #[strum(
+        to_string = "SHA-512/256",
+        serialize = "SHA512/256",
+        serialize = "SHA512-256",
+        serialize = "SHA512t256"
+    )]
+    Sha512_256,
     Sha3_224,
     Sha3_256,
     Sha3_384,
@@ -137,6 +656,17 @@ pub enum HashAlgorithm {
     Shabal384,
     #[strum(serialize = "Shabal-512")]
     Shabal512,
+    Shake128,
+    Shake256,
+    #[cfg(feature = "skein")]
+    #[strum(serialize = "Skein-256")]
+    Skein256,
+    #[cfg(feature = "skein")]
+    #[strum(serialize = "Skein-512")]
+    Skein512,
+    #[cfg(feature = "skein")]
+    #[strum(serialize = "Skein-1024")]
+    Skein1024,
     Sm3,
     #[cfg(feature = "streebog")]
     #[strum(serialize = "Streebog-256")]
@@ -146,8 +676,15 @@ pub enum HashAlgorithm {
     Streebog512,
     #[strum(to_string = "Tiger")]
     Tiger,
+    Tth,
     #[strum(to_string = "Whirlpool")]
     Whirlpool,
+    Xxh32,
+    Xxh64,
+    #[strum(to_string = "XXH3-64", serialize = "XXH3")]
+    Xxh3_64,
+    #[strum(to_string = "XXH3-128", serialize = "XXH128")]
+    Xxh3_128,
 }
 
 #[derive(
@@ -156,13 +693,293 @@ pub enum HashAlgorithm {
 #[strum(serialize_all = "UPPERCASE", ascii_case_insensitive)]
 #[clap(rename_all = "lower")]
 pub enum Style {
-    Sfv,
+    Gnu,
     Bsd,
+    Sfv,
     Json,
+    #[cfg(feature = "md4")]
+    Ed2k,
+    Sri,
+    Hashdeep,
 }
 
 impl Default for Style {
     fn default() -> Self {
-        Self::Sfv
+        Self::Gnu
+    }
+}
+
+/// The representation of the message digests in the output.
+#[derive(Clone, Copy, Default)]
+pub struct DigestFormat {
+    /// The self-describing format to wrap the message digests in, if any.
+    pub multiformat: Option<Multiformat>,
+    /// The multibase to encode the message digests in, if any.
+    pub multibase: Option<Multibase>,
+    /// The encoding of the message digests, if it differs from the default
+    /// encoding of the hash algorithm.
+    pub encoding: Option<Encoding>,
+}
+
+/// The self-describing formats of the multiformats project.
+#[derive(Clone, Copy)]
+pub enum Multiformat {
+    /// The multihash, which is the message digest prefixed with the
+    /// multicodec code of the hash algorithm and the length.
+    Multihash,
+    /// The CIDv1, which is the multihash of raw binary content.
+    Cid,
+}
+
+#[derive(ArgEnum, Clone, Copy)]
+pub enum Multibase {
+    Base16,
+    Base32,
+    Base58btc,
+    Base64,
+    Base64url,
+}
+
+impl Default for Multibase {
+    fn default() -> Self {
+        Self::Base32
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    Hex,
+    UpperHex,
+    Base32,
+    Nix32,
+    Base64,
+    Base64url,
+    Base58,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_algorithm() {
+        let algorithm: Algorithm = "SHA256".parse().unwrap();
+        assert_eq!(algorithm, HashAlgorithm::Sha256.into());
+        assert_eq!(algorithm.to_string(), "SHA256");
+
+        let algorithm: Algorithm = "hmac-sha3-256".parse().unwrap();
+        assert_eq!(
+            algorithm,
+            Algorithm::new(HashAlgorithm::Sha3_256, true, None).unwrap()
+        );
+        assert_eq!(algorithm.to_string(), "HMAC-SHA3-256");
+
+        let algorithm: Algorithm = "BLAKE2b-256".parse().unwrap();
+        assert_eq!(
+            algorithm,
+            Algorithm::new(HashAlgorithm::Blake2b, false, Some(256)).unwrap()
+        );
+        assert_eq!(algorithm.to_string(), "BLAKE2b-256");
+        assert_eq!("BLAKE2b-512".parse::<Algorithm>().unwrap().length, None);
+
+        let algorithm: Algorithm = "git-sha256".parse().unwrap();
+        assert_eq!(algorithm, Algorithm::git(HashAlgorithm::Sha256).unwrap());
+        assert_eq!(algorithm.to_string(), "GIT-SHA256");
+
+        let algorithm: Algorithm = "torrent-sha256".parse().unwrap();
+        assert_eq!(
+            algorithm,
+            Algorithm::torrent(HashAlgorithm::Sha256).unwrap()
+        );
+        assert_eq!(algorithm.to_string(), "TORRENT-SHA256");
+        assert!("TORRENT-MD5".parse::<Algorithm>().is_err());
+
+        let algorithm: Algorithm = "TREE-EXEC-BLAKE2b-256".parse().unwrap();
+        assert_eq!(
+            algorithm,
+            Algorithm::new(HashAlgorithm::Blake2b, false, Some(256))
+                .unwrap()
+                .into_tree(true)
+        );
+        assert_eq!(algorithm.to_string(), "TREE-EXEC-BLAKE2b-256");
+        assert!(!"tree-sha256".parse::<Algorithm>().unwrap().executable);
+
+        assert!("SHA256-128".parse::<Algorithm>().is_err());
+        assert!("GIT-MD5".parse::<Algorithm>().is_err());
+        assert!("UNKNOWN".parse::<Algorithm>().is_err());
+    }
+
+    #[test]
+    fn round_trip_algorithm() {
+        for &hash in HashAlgorithm::value_variants() {
+            let algorithm = Algorithm::from(hash);
+            assert_eq!(
+                algorithm.to_string().parse::<Algorithm>().unwrap(),
+                algorithm
+            );
+        }
+
+        for name in ["SHA-512/224", "SHA512/224", "SHA512-224", "sha512t224"] {
+            let algorithm: Algorithm = name.parse().unwrap();
+            assert_eq!(algorithm, HashAlgorithm::Sha512_224.into());
+            assert_eq!(algorithm.to_string(), "SHA-512/224");
+        }
+        assert_eq!(
+            "sha512-256".parse::<Algorithm>().unwrap(),
+            HashAlgorithm::Sha512_256.into()
+        );
+    }
+
+    #[test]
+    fn parse_bsd_checksum() {
+        let checksum: Checksum =
+            "SHA-512/256 (foo.txt) = 330c723f25267587db0b9f493463e017011239169cb57a6db216c63774367115"
+                .parse()
+                .unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Sha512_256.into()));
+        assert_eq!(checksum.file, PathBuf::from("foo.txt"));
+
+        let checksum: Checksum =
+            "SHA512t224 (foo.txt) = 32620068b859669b45b31008e08b7384649ad2ca3f5163a3a71e5745"
+                .parse()
+                .unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Sha512_224.into()));
+
+        let checksum: Checksum = "TTH (foo.txt) = LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ"
+            .parse()
+            .unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Tth.into()));
+        assert_eq!(checksum.digest.len(), 24);
+    }
+
+    #[test]
+    fn parse_escaped_checksum() {
+        let checksum: Checksum = r"\ebe6c6e6 *a\\b\nc ".parse().unwrap();
+        assert_eq!(checksum.file, PathBuf::from("a\\b\nc "));
+
+        let checksum: Checksum = r"\CRC32 (a\rb) = ebe6c6e6".parse().unwrap();
+        assert_eq!(checksum.file, PathBuf::from("a\rb"));
+
+        let checksum: Checksum = r"ebe6c6e6   a\nb".parse().unwrap();
+        assert_eq!(checksum.file, PathBuf::from(r" a\nb"));
+        assert!(r"\ebe6c6e6  a\tb".parse::<Checksum>().is_err());
+    }
+
+    #[test]
+    fn parse_encoded_checksum() {
+        let digest = "315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3";
+
+        let checksum: Checksum = "SHA256 (foo.txt) = MV9b23bQeMQ7isAGTkoBZGErH853yGk0W/yUx1iU7dM="
+            .parse()
+            .unwrap();
+        assert_eq!(hex::encode(checksum.digest), digest);
+
+        let checksum = Checksum::parse(
+            b"1lzdjiccg57wbcs6kj3prqgjnqb40554w1n0i8xw8y6hfvdmnpri  foo.txt",
+            Some(HashAlgorithm::Sha256.into()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(checksum.algorithm, None);
+        assert_eq!(hex::encode(checksum.digest), digest);
+
+        let checksum = Checksum::parse(b"foo.txt 6+bG5g==", None, Some(Encoding::Base64)).unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Crc32.into()));
+        assert_eq!(checksum.digest, [0xeb, 0xe6, 0xc6, 0xe6]);
+        assert!("foo.txt 6+bG5g==".parse::<Checksum>().is_err());
+
+        // The string is valid in both base32 and the base32 of Nix, so both
+        // decodings are accepted.
+        let checksum = Checksum::parse(
+            b"2wbif5qp2wbif5qp2wbif5qp2wbif5qp  foo.txt",
+            Some(HashAlgorithm::Ripemd160.into()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(checksum.alternatives.len(), 1);
+        assert!([&checksum.digest, &checksum.alternatives[0]].contains(&&vec![0x17; 20]));
+    }
+
+    #[test]
+    fn parse_text_line() {
+        let lines = [
+            "Hello, world!",
+            "foo  bar",
+            "Hi  there",
+            "Checksums  of the release",
+            "Note (foo.txt) = something",
+            "SHA256 (foo.txt) = something",
+            "foo.txt bar",
+        ];
+        for line in lines {
+            assert!(line.parse::<Checksum>().is_err(), "{}", line);
+            assert!(
+                Checksum::parse(line.as_bytes(), Some(HashAlgorithm::Sha256.into()), None).is_err(),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn parse_non_utf8_file_name() {
+        use std::os::unix::ffi::OsStrExt;
+
+        // The file name is "café.txt" in ISO-8859-1.
+        let gnu = b"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  caf\xe9.txt";
+        let bsd = b"SHA256 (caf\xe9.txt) = \
+                    e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        for line in [gnu.as_slice(), bsd] {
+            let checksum = Checksum::parse(line, None, None).unwrap();
+            assert_eq!(checksum.file.as_os_str().as_bytes(), b"caf\xe9.txt");
+            assert_eq!(
+                checksum.output(Style::Gnu, DigestFormat::default(), false),
+                gnu
+            );
+        }
+    }
+
+    #[test]
+    fn parse_multihash_checksum() {
+        let multihash = "z2DrjgbGVmYjsXDtZBXSm9xpDBLeHdXk2JMuz3LgirLJT7m8KZH";
+        let checksum: Checksum = format!("BLAKE2b-256 (foo.txt) = {}", multihash)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            checksum.algorithm,
+            Some(Algorithm::new(HashAlgorithm::Blake2b, false, Some(256)).unwrap())
+        );
+
+        let checksum: Checksum = format!("{}  foo.txt", multihash).parse().unwrap();
+        assert_eq!(
+            checksum.algorithm.map(|a| a.hash),
+            Some(HashAlgorithm::Blake2b)
+        );
+
+        // The hash algorithm disagrees with the multicodec.
+        assert!(format!("SHA256 (foo.txt) = {}", multihash)
+            .parse::<Checksum>()
+            .is_err());
+
+        // The plain hexadecimal is decoded as is, even if it starts with the
+        // prefix of a multibase.
+        let digest = "b".repeat(64);
+        let checksum: Checksum = format!("SHA256 (foo.txt) = {}", digest).parse().unwrap();
+        assert_eq!(hex::encode(checksum.digest), digest);
+    }
+
+    #[test]
+    fn parse_sfv_checksum() {
+        let checksum: Checksum = "foo bar.txt EBE6C6E6".parse().unwrap();
+        assert_eq!(checksum.algorithm, Some(HashAlgorithm::Crc32.into()));
+        assert_eq!(checksum.file, PathBuf::from("foo bar.txt"));
+        assert_eq!(checksum.digest, [0xeb, 0xe6, 0xc6, 0xe6]);
+
+        assert!("foo.txt F58336A78B6F9476".parse::<Checksum>().is_err());
+
+        let checksum: Checksum = "ebe6c6e6  foo.txt".parse().unwrap();
+        assert_eq!(checksum.algorithm, None);
+        assert_eq!(checksum.file, PathBuf::from("foo.txt"));
     }
 }
@@ -4,69 +4,115 @@
 // Copyright (C) 2021 Shun Sakai
 //
 
-use std::fs;
-use std::io::{self, Read};
-use std::path::PathBuf;
+use std::fs::File;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::Serialize;
 
-use crate::value::{Checksum, HashAlgorithm};
+use crate::escape;
+use crate::value::{Algorithm, Checksum, Params};
+use crate::walk::Filter;
 
 pub const VERIFICATION_RESULT_WIDTH: usize = if cfg!(windows) { 79 } else { 80 };
 
 #[derive(Clone, Serialize)]
 pub struct Verify {
-    pub algorithm: HashAlgorithm,
+    pub algorithm: Algorithm,
     pub file: PathBuf,
     pub success: Option<bool>,
+    /// The byte ranges of the corrupted pieces of a file in a torrent.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub corrupted: Vec<Range<u64>>,
 }
 
 impl Verify {
     /// Verify a checksum.
-    pub fn check(checksum: &Checksum) -> Result<Self> {
+    ///
+    /// The filter is used for computing the git tree ID or the tree digest of
+    /// a directory.
+    pub fn check(checksum: &Checksum, params: &Params, filter: &Filter) -> Result<Self> {
         let algorithm = checksum.algorithm.expect("Hash algorithm is unknown");
 
-        if !checksum.file.exists() && atty::is(atty::Stream::Stdin) {
+        let is_stdin = checksum.file == Path::new("-") && atty::isnt(atty::Stream::Stdin);
+
+        if !checksum.file.exists() && !is_stdin {
             return Ok(Self {
                 algorithm,
                 file: checksum.file.clone(),
                 success: None,
+                corrupted: Vec::new(),
+            });
+        }
+
+        // The tree digest is computed only for directories.
+        if algorithm.tree && !checksum.file.is_dir() {
+            return Ok(Self {
+                algorithm,
+                file: checksum.file.clone(),
+                success: Some(false),
+                corrupted: Vec::new(),
             });
         }
 
-        let data = if atty::isnt(atty::Stream::Stdin) {
-            let mut buf = Vec::new();
-            io::stdin().read_to_end(&mut buf)?;
-            buf
+        let (result, length) = if algorithm.git && is_stdin {
+            Checksum::git_stdin(&[algorithm], io::stdin())?
+        } else if algorithm.git {
+            Checksum::git_object(&[algorithm], &checksum.file, filter)?
+        } else if algorithm.torrent {
+            Checksum::info_hash(&[algorithm], params, &checksum.file, filter)?
+        } else if algorithm.tree {
+            Checksum::tree_digest(&[algorithm], params, &checksum.file, filter)?
+        } else if is_stdin {
+            Checksum::digest_reader(&[algorithm], params, &checksum.file, io::stdin())?
         } else {
-            fs::read(checksum.file.clone())?
+            let file = File::open(&checksum.file)?;
+            Checksum::digest_reader(&[algorithm], params, &checksum.file, file)?
         };
 
-        let result = Checksum::digest(algorithm, &(checksum.file.clone(), data));
-
-        if result.digest == checksum.digest {
+        // The size is also compared if it is recorded, such as in an eD2k
+        // link.
+        let is_match = result[0].digest == checksum.digest
+            || checksum.alternatives.contains(&result[0].digest);
+        if is_match && checksum.size.map_or(true, |s| s == length) {
             Ok(Self {
                 algorithm,
                 file: checksum.file.clone(),
                 success: Some(true),
+                corrupted: Vec::new(),
             })
         } else {
             Ok(Self {
                 algorithm,
                 file: checksum.file.clone(),
                 success: Some(false),
+                corrupted: Vec::new(),
             })
         }
     }
 
+    /// Get the file name for the verification result.
+    ///
+    /// As GNU coreutils does, a file name which contains a backslash, a line
+    /// feed or a carriage return is escaped and prefixed with a backslash, so
+    /// that the result fits in a line.
+    fn file_name(&self) -> String {
+        let file = self.file.to_string_lossy();
+        escape::escape(file.as_bytes()).map_or_else(
+            || file.to_string(),
+            |e| format!("\\{}", String::from_utf8_lossy(&e)),
+        )
+    }
+
     /// Output verification result.
     pub fn output(&self) -> String {
         self.success.map_or_else(
             || {
                 format!(
                     "{:01$} No such file or directory",
-                    self.file.display(),
+                    self.file_name(),
                     VERIFICATION_RESULT_WIDTH - 30
                 )
             },
@@ -74,15 +120,27 @@ impl Verify {
                 if s {
                     format!(
                         "{:01$} OK",
-                        self.file.display(),
+                        self.file_name(),
                         VERIFICATION_RESULT_WIDTH - 30
                     )
-                } else {
+                } else if self.corrupted.is_empty() {
                     format!(
                         "{:01$} FAILED",
-                        self.file.display(),
+                        self.file_name(),
                         VERIFICATION_RESULT_WIDTH - 30
                     )
+                } else {
+                    let ranges: Vec<_> = self
+                        .corrupted
+                        .iter()
+                        .map(|r| format!("{}-{}", r.start, r.end - 1))
+                        .collect();
+                    format!(
+                        "{:01$} FAILED (corrupted bytes: {2})",
+                        self.file_name(),
+                        VERIFICATION_RESULT_WIDTH - 30,
+                        ranges.join(", ")
+                    )
                 }
             },
         )
@@ -96,6 +154,7 @@ mod tests {
     use tempfile::NamedTempFile;
 
     use super::*;
+    use crate::value::HashAlgorithm;
 
     #[test]
     #[ignore]
@@ -104,7 +163,12 @@ mod tests {
         let data = "Hello, world!";
         write!(file, "{}", data).unwrap();
         let checksum = Checksum::digest(HashAlgorithm::Blake2b, &(file.path(), data));
-        let result = Verify::check(&checksum).unwrap();
+        let result = Verify::check(
+            &checksum,
+            &Params::default(),
+            &Filter::new(&[], &[], false).unwrap(),
+        )
+        .unwrap();
 
         assert!(result.success.unwrap());
         assert!(result.output().ends_with("OK"));
@@ -118,7 +182,12 @@ mod tests {
         write!(file, "{}", data).unwrap();
         let checksum = Checksum::digest(HashAlgorithm::Blake2b, &(file.path(), data));
         write!(file, ", world!").unwrap();
-        let result = Verify::check(&checksum).unwrap();
+        let result = Verify::check(
+            &checksum,
+            &Params::default(),
+            &Filter::new(&[], &[], false).unwrap(),
+        )
+        .unwrap();
 
         assert!(!result.success.unwrap());
         assert!(result.output().ends_with("FAILED"));
@@ -132,9 +201,26 @@ mod tests {
         write!(file, "{}", data).unwrap();
         let checksum = Checksum::digest(HashAlgorithm::Blake2b, &(file.path(), data));
         file.close().unwrap();
-        let result = Verify::check(&checksum).unwrap();
+        let result = Verify::check(
+            &checksum,
+            &Params::default(),
+            &Filter::new(&[], &[], false).unwrap(),
+        )
+        .unwrap();
 
         assert!(result.success.is_none());
         assert!(result.output().ends_with("No such file or directory"));
     }
+
+    #[test]
+    fn output_escaped_file_name() {
+        let result = Verify {
+            algorithm: HashAlgorithm::Sha256.into(),
+            file: PathBuf::from("a\nb"),
+            success: Some(true),
+            corrupted: Vec::new(),
+        };
+        assert!(result.output().starts_with("\\a\\nb "));
+        assert!(!result.output().contains('\n'));
+    }
 }
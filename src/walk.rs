@@ -0,0 +1,296 @@
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+//
+// Copyright (C) 2021 Shun Sakai
+//
+
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::Gitignore;
+use walkdir::WalkDir;
+
+/// The names of the files that contain ignore patterns, in order of
+/// precedence.
+const IGNORE_FILES: [&str; 2] = [".ignore", ".gitignore"];
+
+/// A filter that selects the files to compute or verify the checksums.
+#[derive(Clone)]
+pub struct Filter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    gitignore: bool,
+    ignores: HashMap<PathBuf, Vec<Gitignore>>,
+}
+
+impl Filter {
+    /// Create a new filter.
+    ///
+    /// If `gitignore` is `true`, the files ignored by `.gitignore` and
+    /// `.ignore` files are also skipped.
+    pub fn new(include: &[String], exclude: &[String], gitignore: bool) -> Result<Self> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_glob_set(include)?)
+        };
+        let exclude = build_glob_set(exclude)?;
+
+        Ok(Self {
+            include,
+            exclude,
+            gitignore,
+            ignores: HashMap::new(),
+        })
+    }
+
+    /// Return `true` if the file is selected by the filter.
+    ///
+    /// The path `-` (stdin) is always selected.
+    pub fn is_match(&mut self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+
+        if path == Path::new("-") {
+            return true;
+        }
+
+        if let Some(ref include) = self.include {
+            if !include.is_match(path) {
+                return false;
+            }
+        }
+
+        !self.is_ignored(path, false)
+    }
+
+    /// Return `true` if the path or any of its parent directories is
+    /// excluded.
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        if path
+            .ancestors()
+            .filter(|p| !p.as_os_str().is_empty())
+            .any(|p| self.exclude.is_match(p))
+        {
+            return true;
+        }
+
+        if !self.gitignore {
+            return false;
+        }
+
+        if path.components().any(|c| c.as_os_str() == ".git") {
+            return true;
+        }
+
+        let dirs: Vec<_> = path
+            .parent()
+            .map(|p| p.ancestors().map(Path::to_path_buf).collect())
+            .unwrap_or_default();
+        for dir in dirs {
+            for ignore in self.ignores(&dir) {
+                let matched = ignore.matched_path_or_any_parents(path, is_dir);
+
+                if matched.is_ignore() {
+                    return true;
+                }
+                if matched.is_whitelist() {
+                    return false;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Get the ignore files in the directory.
+    fn ignores(&mut self, dir: &Path) -> &[Gitignore] {
+        self.ignores.entry(dir.to_path_buf()).or_insert_with(|| {
+            let base = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+
+            IGNORE_FILES
+                .iter()
+                .map(|f| base.join(f))
+                .filter(|f| f.is_file())
+                .map(|f| Gitignore::new(f).0)
+                .collect()
+        })
+    }
+}
+
+/// Build a set of glob patterns.
+///
+/// A pattern is matched at any depth, so `target` matches `foo/target` as
+/// well.
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let pattern = pattern.trim_end_matches('/');
+
+        for glob in [pattern.to_string(), format!("**/{}", pattern)] {
+            builder.add(
+                GlobBuilder::new(&glob)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| format!("Invalid glob pattern: {}", pattern))?,
+            );
+        }
+    }
+
+    builder
+        .build()
+        .context("Failed to build a set of glob patterns")
+}
+
+/// Collect the regular files in the directory recursively.
+///
+/// The returned paths are prefixed with the specified directory and sorted
+/// by file name. The files and directories not selected by the filter are
+/// skipped. Symbolic links to files are followed, but symbolic links to
+/// directories are not. The entries which cannot be read are skipped with a
+/// warning.
+pub fn walk(dir: impl AsRef<Path>, filter: &mut Filter) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = WalkDir::new(dir.as_ref()).sort_by_file_name().into_iter();
+
+    while let Some(entry) = entries.next() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(error) if error.depth() > 0 => {
+                eprintln!("RSHash: WARNING: {}", error);
+
+                continue;
+            }
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!("Failed to read the directory {}", dir.as_ref().display())
+                })
+            }
+        };
+
+        if entry.file_type().is_dir() {
+            if entry.depth() > 0 && filter.is_ignored(entry.path(), true) {
+                entries.skip_current_dir();
+            }
+
+            continue;
+        }
+
+        let is_file =
+            entry.file_type().is_file() || entry.file_type().is_symlink() && entry.path().is_file();
+        if is_file && filter.is_match(entry.path()) {
+            files.push(entry.into_path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Return `true` if the file is executable by the owner.
+#[cfg(unix)]
+pub fn is_executable(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o100 != 0
+}
+
+/// Return `true` if the file is executable by the owner.
+#[cfg(not(unix))]
+pub fn is_executable(_: &Metadata) -> bool {
+    false
+}
+
+/// Convert the file name or path to bytes.
+///
+/// On non-Unix platforms, the name must be valid UTF-8 and `\` is replaced
+/// with `/`.
+#[cfg(unix)]
+pub fn to_bytes(s: &OsStr) -> Result<Vec<u8>> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(s.as_bytes().to_vec())
+}
+
+/// Convert the file name or path to bytes.
+///
+/// On non-Unix platforms, the name must be valid UTF-8 and `\` is replaced
+/// with `/`.
+#[cfg(not(unix))]
+pub fn to_bytes(s: &OsStr) -> Result<Vec<u8>> {
+    s.to_str()
+        .map(|s| s.replace('\\', "/").into_bytes())
+        .with_context(|| format!("{:?} is not valid UTF-8", s))
+}
+
+/// Convert the bytes to a file name or path.
+///
+/// On non-Unix platforms, the bytes must be valid UTF-8.
+#[cfg(unix)]
+pub fn from_bytes(bytes: &[u8]) -> Result<OsString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(OsStr::from_bytes(bytes).to_os_string())
+}
+
+/// Convert the bytes to a file name or path.
+///
+/// On non-Unix platforms, the bytes must be valid UTF-8.
+#[cfg(not(unix))]
+pub fn from_bytes(bytes: &[u8]) -> Result<OsString> {
+    String::from_utf8(bytes.to_vec())
+        .map(OsString::from)
+        .with_context(|| format!("{:?} is not valid UTF-8", String::from_utf8_lossy(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_files() {
+        let mut filter = Filter::new(&["*.rs".to_string()], &[], false).unwrap();
+
+        assert!(filter.is_match("main.rs"));
+        assert!(filter.is_match("src/main.rs"));
+        assert!(!filter.is_match("src/main.o"));
+        assert!(filter.is_match("-"));
+    }
+
+    #[test]
+    fn exclude_files() {
+        let mut filter =
+            Filter::new(&[], &["target/".to_string(), "*.o".to_string()], false).unwrap();
+
+        assert!(filter.is_match("src/main.rs"));
+        assert!(!filter.is_match("src/main.o"));
+        assert!(!filter.is_match("target/debug/rshash"));
+        assert!(!filter.is_match("foo/target/debug/rshash"));
+        assert!(filter.is_match("src/target.rs"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_symlinks() {
+        use std::fs;
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        symlink(dir.path().join("a.txt"), dir.path().join("b.txt")).unwrap();
+        symlink(dir.path().join("sub"), dir.path().join("c")).unwrap();
+        symlink(dir.path().join("missing"), dir.path().join("d.txt")).unwrap();
+
+        let mut filter = Filter::new(&[], &[], false).unwrap();
+        let files = walk(dir.path(), &mut filter).unwrap();
+        assert_eq!(files, [dir.path().join("a.txt"), dir.path().join("b.txt")]);
+    }
+}
@@ -16,15 +16,15 @@ fn command() -> Command {
 
 #[test]
 #[cfg(unix)]
-fn sfv_style_output() {
+fn gnu_style_output() {
     command()
         .arg("-H")
         .arg("blake2b")
         .arg("-s")
-        .arg("sfv")
+        .arg("gnu")
         .write_stdin("Hello, world!")
         .assert()
-        .stdout(predicate::eq(include_str!("resource/checksum/sfv.b2b")));
+        .stdout(predicate::eq(include_str!("resource/checksum/gnu.b2b")));
 }
 
 #[test]
@@ -40,6 +40,87 @@ fn bsd_style_output() {
         .stdout(predicate::eq(include_str!("resource/checksum/bsd.b2b")));
 }
 
+#[test]
+#[cfg(unix)]
+fn tag_output() {
+    command()
+        .arg("-H")
+        .arg("blake2b")
+        .arg("--tag")
+        .write_stdin("Hello, world!")
+        .assert()
+        .stdout(predicate::eq(include_str!("resource/checksum/bsd.b2b")));
+}
+
+#[test]
+fn binary_output() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("-b")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::eq(
+            "315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3 \
+             *resource/tree/hello.txt\n",
+        ));
+}
+
+#[test]
+fn coreutils_verification() {
+    command()
+        .arg("-c")
+        .arg("-H")
+        .arg("sha256")
+        .arg("--strict")
+        .arg("resource/checksum/coreutils.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+#[cfg(unix)]
+fn non_utf8_file_name() {
+    use std::os::unix::ffi::OsStrExt;
+
+    // The file name is "café.txt" in ISO-8859-1, and the manifest also contains
+    // a file with a valid UTF-8 name.
+    let dir = tempfile::tempdir().unwrap();
+    let file = std::ffi::OsStr::from_bytes(b"caf\xe9.txt");
+    std::fs::write(dir.path().join(file), "").unwrap();
+    std::fs::copy(
+        "tests/resource/tree/hello.txt",
+        dir.path().join("hello.txt"),
+    )
+    .unwrap();
+    let checksums: &[u8] = b"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  caf\xe9.txt\n\
+                             315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3  hello.txt\n";
+
+    command()
+        .current_dir(dir.path())
+        .arg("-H")
+        .arg("sha256")
+        .arg(file)
+        .arg("hello.txt")
+        .assert()
+        .success()
+        .stdout(predicate::eq(checksums));
+
+    std::fs::write(dir.path().join("SHA256SUMS"), checksums).unwrap();
+    command()
+        .current_dir(dir.path())
+        .arg("-c")
+        .arg("-H")
+        .arg("sha256")
+        .arg("--strict")
+        .arg("SHA256SUMS")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?m)^hello.txt +OK$").unwrap())
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
 #[test]
 #[cfg(unix)]
 fn json_style_output() {
@@ -53,13 +134,111 @@ fn json_style_output() {
         .stdout(predicate::eq(include_str!("resource/checksum/json.b2b")));
 }
 
+#[test]
+fn sfv_style_output() {
+    command()
+        .arg("-H")
+        .arg("crc32")
+        .arg("-s")
+        .arg("sfv")
+        .arg("resource/tree/hello.txt")
+        .arg("resource/tree/sub/goodbye.txt")
+        .assert()
+        .stdout(predicate::str::diff(
+            include_str!("resource/checksum/crc32.sfv")
+                .lines()
+                .skip(1)
+                .map(|l| format!("{}\n", l))
+                .collect::<String>(),
+        ));
+}
+
+#[test]
+fn sfv_verification() {
+    command()
+        .arg("-c")
+        .arg("--strict")
+        .arg("resource/checksum/crc32.sfv")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+#[cfg(all(feature = "md4", feature = "sha-1"))]
+fn ed2k_style_output() {
+    command()
+        .arg("-H")
+        .arg("ed2k,aich")
+        .arg("-s")
+        .arg("ed2k")
+        .arg("resource/tree/hello.txt")
+        .arg("resource/tree/sub/goodbye.txt")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/ed2k.txt"
+        )));
+}
+
+#[test]
+#[cfg(feature = "md4")]
+fn ed2k_style_checked_before_reading() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("ed2k")
+        .arg("resource/tree/missing.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The ed2k style supports only ED2K and AICH",
+        ));
+}
+
+#[test]
+#[cfg(feature = "md4")]
+fn ed2k_style_verification() {
+    // The eD2k links contain only the file names, so the files are put in the
+    // same directory.
+    let dir = tempfile::tempdir().unwrap();
+    for file in ["hello.txt", "sub/goodbye.txt"] {
+        let path = std::path::Path::new("tests/resource/tree").join(file);
+        std::fs::copy(&path, dir.path().join(path.file_name().unwrap())).unwrap();
+    }
+    let checksums = std::fs::canonicalize("tests/resource/checksum/ed2k.txt").unwrap();
+    command()
+        .current_dir(dir.path())
+        .arg("-c")
+        .arg(&checksums)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match("(?m)^hello.txt +OK$").unwrap())
+        .stdout(predicate::str::is_match("(?m)^goodbye.txt +OK$").unwrap())
+        .stderr(predicate::str::contains("Everything is successful"));
+
+    // A link fails if the size of the file differs.
+    std::fs::write(
+        dir.path().join("ed2k.txt"),
+        "ed2k://|file|hello.txt|14|0ABE9EE1F376CAA1BCECAD9042F16E73|/\n",
+    )
+    .unwrap();
+    command()
+        .current_dir(dir.path())
+        .arg("-c")
+        .arg("ed2k.txt")
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_match("(?m)^hello.txt +FAILED$").unwrap());
+}
+
 #[test]
 fn verification_success() {
     command()
         .arg("-c")
         .arg("-H")
         .arg("blake2b")
-        .arg("resource/checksum/sfv.b2b")
+        .arg("resource/checksum/gnu.b2b")
         .write_stdin("Hello, world!")
         .assert()
         .success()
@@ -72,9 +251,626 @@ fn verification_failure() {
         .arg("-c")
         .arg("-H")
         .arg("blake2b")
-        .arg("resource/checksum/sfv.b2b")
+        .arg("resource/checksum/gnu.b2b")
         .write_stdin("hELLO, WORLD!")
         .assert()
         .failure()
         .stdout(predicate::str::contains("FAILED"));
 }
+
+#[test]
+fn verification_with_piped_stdin() {
+    command()
+        .arg("-c")
+        .arg("-H")
+        .arg("blake2b")
+        .arg("resource/checksum/recursive.b2b")
+        .write_stdin("hELLO, WORLD!")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+#[cfg(unix)]
+fn recursive_output() {
+    command()
+        .arg("-H")
+        .arg("blake2b")
+        .arg("-r")
+        .arg("resource/tree")
+        .assert()
+        .stdout(predicate::eq(include_str!(
+            "resource/checksum/recursive.b2b"
+        )));
+}
+
+#[test]
+#[cfg(unix)]
+fn recursive_output_with_exclude() {
+    command()
+        .arg("-H")
+        .arg("blake2b")
+        .arg("-r")
+        .arg("--exclude")
+        .arg("sub")
+        .arg("resource/tree")
+        .assert()
+        .stdout(
+            predicate::str::contains("resource/tree/hello.txt")
+                .and(predicate::str::contains("resource/tree/sub").not()),
+        );
+}
+
+#[test]
+#[cfg(unix)]
+fn multiple_hash_algorithms_output() {
+    command()
+        .arg("-H")
+        .arg("sha256,blake2b")
+        .arg("-s")
+        .arg("bsd")
+        .write_stdin("Hello, world!")
+        .assert()
+        .stdout(predicate::str::diff(format!(
+            "{}\n{}",
+            "SHA256 (-) = 315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3",
+            include_str!("resource/checksum/bsd.b2b")
+        )));
+}
+
+#[test]
+fn multiple_hash_algorithms_gnu_style_output() {
+    command()
+        .arg("-H")
+        .arg("sha256,sha3-256")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("SHA256 (resource/tree/hello.txt) = ").and(
+                predicate::str::contains("\nSHA3-256 (resource/tree/hello.txt) = "),
+            ),
+        );
+
+    let dir = tempfile::tempdir().unwrap();
+    command()
+        .arg("-H")
+        .arg("sha256,sha3-256")
+        .arg("-o")
+        .arg(dir.path())
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("SHA256SUMS")).unwrap(),
+        "315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3  \
+         resource/tree/hello.txt\n"
+    );
+    assert!(dir.path().join("SHA3-256SUMS").is_file());
+}
+
+#[test]
+fn hmac_output() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("--hmac")
+        .arg("--key-file")
+        .arg("resource/key")
+        .arg("-s")
+        .arg("bsd")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::eq(include_str!("resource/checksum/hmac.sha256")));
+}
+
+#[test]
+fn hmac_verification() {
+    command()
+        .arg("-c")
+        .arg("--key-file")
+        .arg("resource/key")
+        .arg("resource/checksum/hmac.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn variable_length_output() {
+    command()
+        .arg("-H")
+        .arg("blake2b")
+        .arg("-l")
+        .arg("256")
+        .arg("-s")
+        .arg("bsd")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::eq(include_str!("resource/checksum/bsd-256.b2b")));
+}
+
+#[test]
+fn variable_length_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/bsd-256.b2b")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn git_object_output() {
+    command()
+        .arg("--git")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("bsd")
+        .arg("resource/tree")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/git.sha256"
+        )));
+}
+
+#[test]
+fn git_blob_of_stdin() {
+    let blob =
+        "GIT-SHA256 (-) = 178b5fbed164aee269fee7323badf7269cca0eed0875717b0d2d4f9819164c3f\n";
+    command()
+        .arg("--git")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("bsd")
+        .write_stdin("Hello, world!")
+        .assert()
+        .stdout(predicate::eq(blob));
+
+    // Stdin redirected from a regular file is streamed.
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin(env!("CARGO_PKG_NAME")))
+        .args(["--git", "-H", "sha256", "-s", "bsd"])
+        .stdin(std::fs::File::open("tests/resource/tree/hello.txt").unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), blob);
+}
+
+#[test]
+fn export_tth_tree_of_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("tree.json");
+    command()
+        .arg("-H")
+        .arg("tth")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("bsd")
+        .arg("--export-tree")
+        .arg(&file)
+        .write_stdin("")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "TTH (-) = LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ\nSHA256 (-) = e3b0c442",
+        ));
+
+    let tree: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(file).unwrap()).unwrap();
+    assert_eq!(
+        tree,
+        serde_json::json!([{
+            "file": "-",
+            "levels": [["LWPNACQDBZRYXW3VHJVCJ64QBZNGHOHHHZWCLNQ"]]
+        }])
+    );
+}
+
+#[test]
+fn export_tree_after_checks() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("tree.json");
+    command()
+        .arg("-H")
+        .arg("tth")
+        .arg("-s")
+        .arg("sri")
+        .arg("--export-tree")
+        .arg(&file)
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The SRI style supports only SHA-256, SHA-384 and SHA-512",
+        ));
+    assert!(!file.exists());
+}
+
+#[test]
+fn git_object_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/git.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn tree_digest_output() {
+    command()
+        .arg("--tree")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("bsd")
+        .arg("resource/tree")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/tree.sha256"
+        )));
+}
+
+#[test]
+fn tree_digest_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/tree.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn info_hash_output() {
+    command()
+        .arg("--torrent")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("bsd")
+        .arg("resource/tree")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/torrent.sha256"
+        )));
+}
+
+#[test]
+fn export_torrent() {
+    let dir = tempfile::tempdir().unwrap();
+    command()
+        .arg("--torrent")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("bsd")
+        .arg("--export-torrent")
+        .arg(dir.path())
+        .arg("resource/tree")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/torrent.sha256"
+        )));
+    assert_eq!(
+        std::fs::read(dir.path().join("tree.torrent")).unwrap(),
+        include_bytes!("resource/checksum/tree.torrent")
+    );
+
+    // The style is checked before the torrents are saved.
+    let dir = tempfile::tempdir().unwrap();
+    command()
+        .arg("--torrent")
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("sfv")
+        .arg("--export-torrent")
+        .arg(dir.path())
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .failure();
+    assert!(!dir.path().join("hello.txt.torrent").exists());
+
+    command()
+        .arg("--piece-length")
+        .arg("16384")
+        .arg("-H")
+        .arg("sha256")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn info_hash_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/torrent.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn torrent_verification() {
+    command()
+        .current_dir("tests/resource")
+        .arg("-c")
+        .arg("checksum/tree.torrent")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("goodbye.txt"))
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn cid_output() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("--cid")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/cid.sha256"
+        )));
+}
+
+#[test]
+fn multihash_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/multihash.txt")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn encoded_output() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("--encoding")
+        .arg("base64")
+        .arg("-s")
+        .arg("bsd")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/base64.sha256"
+        )));
+}
+
+#[test]
+fn encoded_verification() {
+    command()
+        .arg("-c")
+        .arg("-H")
+        .arg("sha256")
+        .arg("resource/checksum/nix32.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn sri_output() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("-H")
+        .arg("sha384")
+        .arg("--style")
+        .arg("sri")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/sri.txt"
+        )));
+}
+
+#[test]
+fn sri_style_checked_before_reading() {
+    command()
+        .arg("-H")
+        .arg("sha224,sha256")
+        .arg("-s")
+        .arg("sri")
+        .arg("resource/tree/missing.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The SRI style supports only SHA-256, SHA-384 and SHA-512",
+        ));
+}
+
+#[test]
+fn sri_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/sri.txt")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+fn html_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/sri.html")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+#[cfg(unix)]
+fn hashdeep_output() {
+    command()
+        .arg("-H")
+        .arg("sha256,tiger")
+        .arg("-s")
+        .arg("hashdeep")
+        .arg("-r")
+        .arg("resource/tree")
+        .assert()
+        .stdout(predicate::str::diff(include_str!(
+            "resource/checksum/hashdeep.txt"
+        )));
+}
+
+#[test]
+fn hashdeep_style_checked_before_reading() {
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("-s")
+        .arg("hashdeep")
+        .arg("--encoding")
+        .arg("base64")
+        .arg("resource/tree/missing.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The hashdeep style supports only hexadecimal",
+        ));
+}
+
+#[test]
+fn hashdeep_verification() {
+    command()
+        .arg("-c")
+        .arg("resource/checksum/hashdeep.txt")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}
+
+#[test]
+#[cfg(unix)]
+fn audit() {
+    command()
+        .arg("-a")
+        .arg("resource/checksum/hashdeep.txt")
+        .arg("-r")
+        .arg("resource/tree")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Audit passed"));
+
+    command()
+        .arg("-a")
+        .arg("resource/checksum/hashdeep.txt")
+        .arg("resource/tree/hello.txt")
+        .arg("resource/key")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("resource/key"))
+        .stderr(predicate::str::contains(
+            "Audit failed (Matched:1; Moved:0; New:1; Missing:1)",
+        ));
+
+    // Only SHA-256 is shared with the known checksums.
+    command()
+        .arg("-H")
+        .arg("sha256")
+        .arg("-a")
+        .arg("resource/checksum/hashdeep.txt")
+        .arg("-r")
+        .arg("resource/tree")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Audit passed"));
+
+    command()
+        .arg("-H")
+        .arg("sha512")
+        .arg("-a")
+        .arg("resource/checksum/hashdeep.txt")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no hash algorithm in common"));
+}
+
+#[test]
+fn format_output() {
+    command()
+        .arg("--format")
+        .arg(r"%{sha256:base64}\t%s\t%f")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .stdout(predicate::eq(
+            "MV9b23bQeMQ7isAGTkoBZGErH853yGk0W/yUx1iU7dM=\t13\thello.txt\n",
+        ));
+
+    command()
+        .arg("-H")
+        .arg("sha256,blake2b")
+        .arg("--format")
+        .arg("%a (%p) = %h")
+        .write_stdin("Hello, world!")
+        .assert()
+        .stdout(predicate::str::diff(format!(
+            "{}\n{}",
+            "SHA256 (-) = 315f5bdb76d078c43b8ac0064e4a0164612b1fce77c869345bfc94c75894edd3",
+            include_str!("resource/checksum/bsd.b2b")
+        )));
+
+    // The hash algorithms in the template are affected by `--git`.
+    command()
+        .arg("--git")
+        .arg("--format")
+        .arg("%{sha256}")
+        .write_stdin("Hello, world!")
+        .assert()
+        .stdout(predicate::eq(
+            "178b5fbed164aee269fee7323badf7269cca0eed0875717b0d2d4f9819164c3f\n",
+        ));
+
+    command()
+        .arg("--format")
+        .arg("unknown")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown format: unknown"));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn config_format_ignored_by_conflicting_options() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("rshash")).unwrap();
+    std::fs::write(
+        dir.path().join("rshash/config.toml"),
+        "format = \"%{sha256} %p\\n\"\n",
+    )
+    .unwrap();
+
+    command()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .arg("-H")
+        .arg("sha256")
+        .arg("--binary")
+        .arg("resource/tree/hello.txt")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(" *resource/tree/hello.txt\n"));
+    command()
+        .env("XDG_CONFIG_HOME", dir.path())
+        .arg("-c")
+        .arg("resource/checksum/tree.sha256")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Everything is successful"));
+}